@@ -1,20 +1,56 @@
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse::Parse, parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Fields,
-    FieldsNamed, FieldsUnnamed, Ident, ItemEnum, LitStr, Result, Type, Variant,
+    parse::Parse,
+    parse_macro_input,
+    visit_mut::{self, VisitMut},
+    Attribute, Data, DeriveInput, Expr, Fields, FieldsNamed, FieldsUnnamed, Ident, ItemEnum,
+    LitFloat, LitInt, LitStr, Member, Result, Token, Type, Variant,
 };
 
+#[derive(Default)]
 struct Args {
     state: Option<Type>,
+    base_path: Option<LitStr>,
+    self_test: bool,
+    current_route: bool,
 }
 
 impl Parse for Args {
     fn parse(input: syn::parse::ParseStream) -> Result<Self> {
-        let state = input.parse::<Type>().ok();
+        let mut args = Args::default();
+        while !input.is_empty() {
+            if input.peek(Ident) && input.peek2(Token![=]) {
+                let key = input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+                match key.to_string().as_str() {
+                    "base_path" => args.base_path = Some(input.parse()?),
+                    _ => return Err(syn::Error::new(key.span(), "Unsupported router arg")),
+                }
+            } else if is_flag(input, "self_test") {
+                input.parse::<Ident>()?;
+                args.self_test = true;
+            } else if is_flag(input, "current_route") {
+                input.parse::<Ident>()?;
+                args.current_route = true;
+            } else {
+                args.state = Some(input.parse()?);
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
 
-        Ok(Self { state })
+        Ok(args)
+    }
+}
+
+fn is_flag(input: syn::parse::ParseStream, flag: &str) -> bool {
+    let fork = input.fork();
+    match fork.parse::<Ident>() {
+        Ok(ident) => ident == flag && (fork.is_empty() || fork.peek(Token![,])),
+        Err(_) => false,
     }
 }
 
@@ -34,9 +70,16 @@ fn router_macro(args: Args, item_enum: ItemEnum) -> Result<TokenStream2> {
         None => quote! { () },
     };
 
+    let base_path = args.base_path.map(|lit| quote! { #[base_path(#lit)] });
+    let self_test = args.self_test.then(|| quote! { #[self_test] });
+    let current_route = args.current_route.then(|| quote! { #[current_route] });
+
     let expanded = quote! {
         #[derive(enum_router::Routes)]
         #[state(#attr)]
+        #base_path
+        #self_test
+        #current_route
         #item_enum
     };
 
@@ -44,252 +87,1479 @@ fn router_macro(args: Args, item_enum: ItemEnum) -> Result<TokenStream2> {
 }
 
 #[proc_macro_attribute]
-pub fn resource(_args: TokenStream, input: TokenStream) -> TokenStream {
-    // let args = parse_macro_input!(args as Args);
+pub fn resource(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as Args);
     let input = parse_macro_input!(input as ItemEnum);
-    match resource_macro(input) {
+    match resource_macro(args, input) {
         Ok(s) => s.to_token_stream().into(),
         Err(e) => e.to_compile_error().into(),
     }
 }
 
-fn resource_macro(item_enum: ItemEnum) -> Result<TokenStream2> {
+fn resource_macro(args: Args, item_enum: ItemEnum) -> Result<TokenStream2> {
     let ident = &item_enum.ident;
     let variants = item_enum
         .variants
         .iter()
-        .map(|variant| RouteVariant::try_from(variant))
+        .map(RouteVariant::try_from)
+        .collect::<Result<Vec<_>>>()?;
+
+    let route_enum = RouteEnum {
+        ident,
+        variants,
+        prefix: format!("/{}", pascal_to_snake(&ident.to_string())),
+        base_path: args.base_path,
+        resource: true,
+        layers: layers(&item_enum.attrs)?,
+        cors: cors(&item_enum.attrs)?,
+    };
+
+    let method_routers = route_enum.method_routers(&quote! { () });
+
+    let href = href_impl(&route_enum);
+    let info = info_impl(&route_enum);
+    let checks = body_checks(&route_enum);
+    let breadcrumbs = breadcrumbs_impl(&route_enum);
+    let sitemap = sitemap_impl(&route_enum);
+    let self_test = self_test_impl(&route_enum, None, args.self_test)?;
+    let from_route = from_route_impl(&route_enum, args.current_route);
+
+    // the enum isn't derived from, so its helper attributes are removed
+    let mut item_enum = item_enum.clone();
+    item_enum
+        .attrs
+        .retain(|attr| !attr.path.is_ident("layer") && !attr.path.is_ident("cors"));
+    for variant in &mut item_enum.variants {
+        variant.attrs.retain(|attr| {
+            !["layer", "csrf", "parent", "title", "sitemap"]
+                .iter()
+                .any(|name| attr.path.is_ident(name))
+        });
+    }
+
+    let expanded = quote! {
+        #[derive(Debug)]
+        #item_enum
+
+        impl #ident {
+            pub fn router() -> ::axum::Router {
+                enum_router::register_routes(Self::ROUTES, Self::__method_routers())
+            }
+
+            #method_routers
+        }
+
+        #href
+        #info
+        #checks
+        #breadcrumbs
+        #sitemap
+        #self_test
+        #from_route
+    };
+
+    Ok(expanded)
+}
+
+#[proc_macro_derive(QueryString)]
+pub fn query_string(s: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(s as DeriveInput);
+    match query_string_macro(input) {
+        Ok(s) => s.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn query_string_macro(input: DeriveInput) -> Result<TokenStream2> {
+    let struct_name = input.ident;
+    let Data::Struct(data) = input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "Only structs are supported",
+        ));
+    };
+    let field_tokens = data
+        .fields
+        .iter()
+        .filter(|field| field.ident.is_some())
+        .map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            let name = ident.to_string();
+            quote! { (#name, self.#ident.as_ref()) }
+        })
+        .collect::<Vec<_>>();
+
+    let field_infos = data
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let name = field.ident.as_ref()?.to_string();
+            let ty = &field.ty;
+            let ty = quote! { #ty }.to_string().replace(' ', "");
+            Some(quote! {
+                enum_router::FieldInfo {
+                    name: #name,
+                    ty: #ty,
+                    query: false,
+                    fields: &[],
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let tokens = quote! {
+        impl #struct_name {
+            #[allow(unused)]
+            pub const QUERY_FIELDS: &'static [enum_router::FieldInfo] = &[#(#field_infos,)*];
+
+            fn query_string(&self) -> String {
+                [#(#field_tokens,)*]
+                    .iter()
+                    .filter(|(_, value)| value.is_some())
+                    .map(|(key, value)| format!("{}={}", key, value.unwrap()))
+                    .collect::<Vec<_>>()
+                    .join("&")
+            }
+        }
+    };
+
+    Ok(tokens)
+}
+
+/// Parses a `QueryString` struct back from its query string, for
+/// `CurrentRoute`. Every field has to implement `FromStr`.
+#[proc_macro_derive(FromQueryString)]
+pub fn from_query_string(s: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(s as DeriveInput);
+    match from_query_string_macro(input) {
+        Ok(s) => s.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn from_query_string_macro(input: DeriveInput) -> Result<TokenStream2> {
+    let struct_name = input.ident;
+    let Data::Struct(data) = input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "Only structs are supported",
+        ));
+    };
+    let field_parsers = data
+        .fields
+        .iter()
+        .filter(|field| field.ident.is_some())
+        .map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            let name = ident.to_string();
+            quote! { #ident: enum_router::current_route::query_value(Some(query), #name) }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl enum_router::current_route::FromQueryString for #struct_name {
+            fn from_query_string(query: &str) -> Self {
+                Self {
+                    #(#field_parsers,)*
+                }
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(
+    Routes,
+    attributes(
+        get,
+        post,
+        delete,
+        patch,
+        put,
+        trace,
+        head,
+        options,
+        connect,
+        route,
+        any,
+        state,
+        base_path,
+        self_test,
+        current_route,
+        resource,
+        query,
+        router,
+        layer,
+        cors,
+        csrf,
+        parent,
+        title,
+        sitemap
+    )
+)]
+pub fn routes(s: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(s as DeriveInput);
+    match routes_macro(input) {
+        Ok(s) => s.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn routes_macro(input: DeriveInput) -> Result<TokenStream2> {
+    let enum_name = input.ident;
+    let Data::Enum(data) = input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "Only enums are supported",
+        ));
+    };
+
+    let arg = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("state"))
+        .filter_map(args)
+        .next_back();
+
+    let state = arg
+        .and_then(|args| args.state)
+        .filter(|state| !matches!(state, Type::Tuple(tuple) if tuple.elems.is_empty()));
+
+    let state_generic = match &state {
+        Some(state) => quote! { #state },
+        None => quote! { () },
+    };
+
+    let is_self_test = input
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident("self_test"));
+
+    let base_path = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("base_path"))
+        .map(|attr| attr.parse_args::<LitStr>())
+        .next_back()
+        .transpose()?;
+
+    let variants = data
+        .variants
+        .iter()
+        .map(RouteVariant::try_from)
         .collect::<Result<Vec<_>>>()?;
 
-    let routes: Vec<TokenStream2> = variants
-        .iter()
-        .map(
-            |RouteVariant {
-                 attr,
-                 path,
-                 variant,
-                 ..
-             }| match attr {
-                Attr::Router(ty) => quote! { .merge(#ty::router()) },
-                method => {
-                    let fn_string = pascal_to_snake(&variant.to_string());
-                    let fn_name = Ident::new(&fn_string, variant.span());
-                    let method = Ident::new(&method.to_string(), variant.span());
-                    let path = format!(
-                        "/{}{}",
-                        pascal_to_snake(&ident.to_string()),
-                        path.value().replace("{{}}", "{id}")
+    let route_enum = RouteEnum {
+        ident: &enum_name,
+        variants,
+        prefix: String::new(),
+        base_path,
+        resource: false,
+        layers: layers(&input.attrs)?,
+        cors: cors(&input.attrs)?,
+    };
+
+    let method_routers = route_enum.method_routers(&state_generic);
+
+    let href = href_impl(&route_enum);
+    let info = info_impl(&route_enum);
+    let checks = body_checks(&route_enum);
+    let breadcrumbs = breadcrumbs_impl(&route_enum);
+    let sitemap = sitemap_impl(&route_enum);
+    let self_test = self_test_impl(&route_enum, state.as_ref(), is_self_test)?;
+    let from_route = from_route_impl(
+        &route_enum,
+        input
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("current_route")),
+    );
+
+    let expanded = quote! {
+        impl #enum_name {
+            pub fn router() -> ::axum::Router<#state_generic> {
+                enum_router::register_routes(Self::ROUTES, Self::__method_routers())
+            }
+
+            #method_routers
+        }
+
+        #href
+        #info
+        #checks
+        #breadcrumbs
+        #sitemap
+        #self_test
+        #from_route
+    };
+
+    Ok(expanded)
+}
+
+/// `FromRoute` for `current_route`, which parses every field with `FromStr`,
+/// so it is opt-in.
+fn from_route_impl(route_enum: &RouteEnum, current_route: bool) -> TokenStream2 {
+    if !current_route {
+        return quote! {};
+    }
+    let ident = route_enum.ident;
+    let enum_name = ident.to_string();
+    let from_route = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let variant = rv.variant;
+            if let Attr::Router(ty) = &rv.attr {
+                return quote! {
+                    if let Some(route) = <#ty as enum_router::current_route::FromRoute>::from_route(route, path, query) {
+                        return Some(Self::#variant(route));
+                    }
+                };
+            }
+            let pattern = route_enum.pattern(rv);
+            let variant_name = variant.to_string();
+            let value = match rv.fields {
+                Fields::Named(fields) => {
+                    let fields = fields.named.iter().map(|field| {
+                        let ident = field.ident.as_ref().unwrap();
+                        let name = ident.to_string();
+                        match is_option(&field.ty) {
+                            true => quote! {
+                                #ident: enum_router::current_route::debug_query_option(query, #name)?
+                            },
+                            false => quote! {
+                                #ident: enum_router::current_route::debug_query_value(query, #name)?
+                            },
+                        }
+                    });
+                    quote! { Self::#variant { #(#fields,)* } }
+                }
+                Fields::Unnamed(fields) => {
+                    let fields = fields.unnamed.iter().enumerate().map(|(i, field)| {
+                        match field.attrs.iter().any(|attr| attr.path.is_ident("query")) {
+                            true => {
+                                let ty = &field.ty;
+                                quote! {
+                                    <#ty as enum_router::current_route::FromQueryString>::from_query_string(
+                                        query.unwrap_or_default(),
+                                    )
+                                }
+                            }
+                            false => quote! { enum_router::current_route::param(&params, #i)? },
+                        }
+                    });
+                    quote! { Self::#variant(#(#fields,)*) }
+                }
+                Fields::Unit => quote! { Self::#variant },
+            };
+            quote! {
+                if route.enum_name == #enum_name
+                    && route.variant == #variant_name
+                    && route.pattern == #pattern
+                {
+                    #[allow(unused)]
+                    let params = enum_router::current_route::params(#pattern, path)?;
+                    return Some(#value);
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl enum_router::current_route::FromRoute for #ident {
+            fn from_route(
+                route: &enum_router::RouteInfo,
+                path: &str,
+                query: Option<&str>,
+            ) -> Option<Self> {
+                #(#from_route)*
+                None
+            }
+        }
+    }
+}
+
+/// `assert_all_routes_resolve`, and with `self_test` the enum's `Samples` and
+/// a `#[test]` running it with them. The test is only run for enums declared
+/// at module level, since tests inside function bodies can't be named.
+fn self_test_impl(
+    route_enum: &RouteEnum,
+    state: Option<&Type>,
+    self_test: bool,
+) -> Result<TokenStream2> {
+    let ident = route_enum.ident;
+    let assert = match state {
+        Some(state) => quote! {
+            /// Panics unless every sample is routed to a handler by `router()`
+            /// and every route has a sample.
+            #[allow(unused)]
+            pub async fn assert_all_routes_resolve(state: #state, samples: impl IntoIterator<Item = Self>) {
+                enum_router::self_test::assert_routes_resolve(Self::router().with_state(state), samples).await
+            }
+        },
+        None => quote! {
+            /// Panics unless every sample is routed to a handler by `router()`
+            /// and every route has a sample.
+            #[allow(unused)]
+            pub async fn assert_all_routes_resolve(samples: impl IntoIterator<Item = Self>) {
+                enum_router::self_test::assert_routes_resolve(Self::router(), samples).await
+            }
+        },
+    };
+
+    if !self_test {
+        return Ok(quote! {
+            impl #ident {
+                #assert
+            }
+        });
+    }
+
+    if state.is_some() {
+        return Err(syn::Error::new(
+            ident.span(),
+            "self_test needs a stateless router, use assert_all_routes_resolve instead",
+        ));
+    }
+
+    let samples = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let variant = rv.variant;
+            match &rv.attr {
+                Attr::Router(ty) => quote! {
+                    samples.extend(
+                        <#ty as enum_router::self_test::Samples>::samples()
+                            .into_iter()
+                            .map(Self::#variant),
                     );
-                    quote! { .route(#path, #method(#ident::#fn_name)) }
+                },
+                _ => {
+                    let sample = |field: &syn::Field| match field
+                        .attrs
+                        .iter()
+                        .any(|attr| attr.path.is_ident("query"))
+                    {
+                        true => quote! { ::core::default::Default::default() },
+                        false => quote! { enum_router::self_test::Sample::sample() },
+                    };
+                    let value = match rv.fields {
+                        Fields::Named(fields) => {
+                            let fields = fields.named.iter().map(|field| {
+                                let name = &field.ident;
+                                let sample = sample(field);
+                                quote! { #name: #sample }
+                            });
+                            quote! { Self::#variant { #(#fields,)* } }
+                        }
+                        Fields::Unnamed(fields) => {
+                            let fields = fields.unnamed.iter().map(sample);
+                            quote! { Self::#variant(#(#fields,)*) }
+                        }
+                        Fields::Unit => quote! { Self::#variant },
+                    };
+                    quote! { samples.push(#value); }
                 }
-            },
-        )
-        .collect();
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let test = Ident::new(
+        &format!("{}_routes_resolve", pascal_to_snake(&ident.to_string())),
+        ident.span(),
+    );
+
+    Ok(quote! {
+        impl #ident {
+            #assert
+        }
+
+        impl enum_router::self_test::Samples for #ident {
+            fn samples() -> Vec<Self> {
+                let mut samples = Vec::new();
+                #(#samples)*
+                samples
+            }
+        }
 
-    let lowercase = ident.to_string().to_lowercase();
+        #[cfg(test)]
+        #[test]
+        fn #test() {
+            enum_router::self_test::block_on(#ident::assert_all_routes_resolve(
+                <#ident as enum_router::self_test::Samples>::samples(),
+            ));
+        }
+    })
+}
 
-    let urls = variants
+fn href_impl(route_enum: &RouteEnum) -> TokenStream2 {
+    let ident = route_enum.ident;
+    let urls = route_enum
+        .variants
         .iter()
         .map(|rv| {
-            let left = left(&ident, &rv.variant, &rv.fields);
-            let right = right(&rv);
+            let left = left(ident, rv.variant, rv.fields);
+            let right = url(rv, &route_enum.prefix, route_enum.base_path.as_ref());
             quote! { #left => #right }
         })
         .collect::<Vec<_>>();
-    let expanded = quote! {
-        #[derive(Debug)]
-        #item_enum
 
-        impl #ident {
-            pub fn router() -> ::axum::Router {
-                use ::axum::routing::{get, post, patch, put, delete, trace, head};
-                ::axum::Router::new()
-                    #(#routes)*
+    let methods = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let left = left(ident, rv.variant, rv.fields);
+            let right = method(rv);
+            quote! { #left => #right }
+        })
+        .collect::<Vec<_>>();
+
+    let body_type = match cfg!(feature = "client") {
+        true => {
+            let body_types = route_enum
+                .variants
+                .iter()
+                .map(|rv| {
+                    let left = left(ident, rv.variant, rv.fields);
+                    let right = match (&rv.attr, &rv.body) {
+                        (Attr::Router(_), _) => {
+                            quote! { enum_router::Routable::__body_type(x0) }
+                        }
+                        (_, Some(body)) => {
+                            let name = quote! { #body }.to_string().replace(' ', "");
+                            quote! { Some((::std::any::TypeId::of::<#body>(), #name)) }
+                        }
+                        (_, None) => quote! { None },
+                    };
+                    quote! { #left => #right }
+                })
+                .collect::<Vec<_>>();
+            quote! {
+                fn __body_type(&self) -> Option<(::std::any::TypeId, &'static str)> {
+                    #[allow(unused_variables)]
+                    match self {
+                        #(#body_types,)*
+                    }
+                }
             }
+        }
+        false => quote! {},
+    };
 
-            pub fn url(&self) -> String {
+    let request = match cfg!(feature = "testing") {
+        true => quote! {
+            /// A test request with this route's method and url.
+            #[allow(unused)]
+            pub fn request(&self) -> ::axum::http::Request<::axum::body::Body> {
+                enum_router::testing::request(self)
+            }
+        },
+        false => quote! {},
+    };
+
+    quote! {
+        impl #ident {
+            #[allow(unused)]
+            pub fn url(&self) -> enum_router::Href {
                 match self {
                     #(#urls,)*
                 }
             }
-        }
-
-        impl core::fmt::Display for #ident {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                let url = self.url();
-                f.write_fmt(format_args!("/{}{}", #lowercase, self.url()))
+
+            #[allow(unused)]
+            pub fn method(&self) -> ::axum::http::Method {
+                match self {
+                    #(#methods,)*
+                }
+            }
+
+            /// Whether `uri` is this route's page, ignoring the query.
+            #[allow(unused)]
+            pub fn is_current(&self, uri: &::axum::http::Uri) -> bool {
+                enum_router::current_route::is_current(&self.url(), uri)
+            }
+
+            /// Whether `uri` is a page below this route, e.g. `/todos/1` for `/todos`.
+            #[allow(unused)]
+            pub fn is_ancestor_of(&self, uri: &::axum::http::Uri) -> bool {
+                enum_router::current_route::is_ancestor_of(&self.url(), uri)
+            }
+
+            #[allow(unused)]
+            pub fn absolute_url(&self, base: &enum_router::BaseUrl) -> enum_router::Href {
+                base.url(self)
+            }
+
+            #request
+
+            #[allow(unused)]
+            pub fn redirect(&self) -> ::axum::response::Response {
+                self.url().redirect()
+            }
+
+            #[allow(unused)]
+            pub fn redirect_permanent(&self) -> ::axum::response::Response {
+                self.url().redirect_permanent()
+            }
+
+            #[allow(unused)]
+            pub fn redirect_temporary(&self) -> ::axum::response::Response {
+                self.url().redirect_temporary()
+            }
+
+            #[allow(unused)]
+            pub fn htmx_redirect(&self) -> ::axum::response::Response {
+                self.url().htmx_redirect()
+            }
+
+            #[allow(unused)]
+            pub fn htmx_location(&self) -> ::axum::response::Response {
+                self.url().htmx_location()
+            }
+        }
+
+        impl enum_router::Routable for #ident {
+            const ROUTES: &'static [enum_router::RouteInfo] = Self::ROUTES;
+
+            fn url(&self) -> enum_router::Href {
+                Self::url(self)
+            }
+
+            fn method(&self) -> ::axum::http::Method {
+                Self::method(self)
+            }
+
+            fn pattern(&self) -> &'static str {
+                Self::pattern(self)
+            }
+
+            fn name(&self) -> &'static str {
+                Self::name(self)
+            }
+
+            fn sitemap_options(&self) -> Option<enum_router::sitemap::Options> {
+                Self::sitemap_options(self)
+            }
+
+            fn __sitemap_units() -> Vec<Self> {
+                Self::__sitemap_units()
+            }
+
+            #body_type
+        }
+
+        impl #ident {
+            /// The `method` attribute and hidden `_method` input of a form
+            /// submitting to this route, for `method_override`.
+            #[allow(unused)]
+            pub fn form_method_fields(&self) -> enum_router::FormMethodFields {
+                enum_router::FormMethodFields::new(self.method())
+            }
+
+            /// `<a href="...">text</a>` for this route, escaped.
+            #[allow(unused)]
+            pub fn link_to(&self, text: impl AsRef<str>) -> String {
+                enum_router::html::link_to(self, text)
+            }
+
+            /// `<form action="..." method="...">` for this route, with the
+            /// hidden `_method` input when needed.
+            #[allow(unused)]
+            pub fn form_open(&self) -> String {
+                enum_router::html::form_open(self, None)
+            }
+        }
+
+        impl ::axum::response::IntoResponse for #ident {
+            fn into_response(self) -> ::axum::response::Response {
+                self.redirect()
+            }
+        }
+
+        impl std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_fmt(format_args!("{}", self.url()))
+            }
+        }
+    }
+}
+
+fn info_impl(route_enum: &RouteEnum) -> TokenStream2 {
+    let ident = route_enum.ident;
+    let enum_name = ident.to_string();
+
+    let patterns = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let left = left(ident, rv.variant, rv.fields);
+            let right = match rv.attr {
+                Attr::Router(_) => quote! { x0.pattern() },
+                _ => {
+                    let pattern = route_enum.pattern(rv);
+                    quote! { #pattern }
+                }
+            };
+            quote! { #left => #right }
+        })
+        .collect::<Vec<_>>();
+
+    let names = |nested: bool| {
+        route_enum
+            .variants
+            .iter()
+            .map(|rv| {
+                let left = left(ident, rv.variant, rv.fields);
+                let right = match rv.attr {
+                    Attr::Router(_) => quote! { x0.__nested_name() },
+                    _ => {
+                        let name = match nested {
+                            true => route_enum.nested_name(rv),
+                            false => route_enum.name(rv),
+                        };
+                        quote! { #name }
+                    }
+                };
+                quote! { #left => #right }
+            })
+            .collect::<Vec<_>>()
+    };
+    let nested_names = names(true);
+    let names = names(false);
+
+    let guards = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let left = left(ident, rv.variant, rv.fields);
+            let right = match rv.attr {
+                Attr::Router(_) => quote! { x0.guards() },
+                _ => {
+                    let guards = guard_names(rv);
+                    quote! { &[#(#guards,)*] }
+                }
+            };
+            quote! { #left => #right }
+        })
+        .collect::<Vec<_>>();
+
+    let sources = |nested: bool| {
+        route_enum
+            .variants
+            .iter()
+            .map(|rv| match &rv.attr {
+                Attr::Router(ty) => quote! { <#ty>::__NESTED_ROUTES },
+                attr => {
+                    let method = attr.method().to_uppercase();
+                    let methods = attr.methods();
+                    let pattern = route_enum.pattern(rv);
+                    let variant = rv.variant.to_string();
+                    let handler = route_enum.handler(rv).to_string().replace(' ', "");
+                    let name = match nested {
+                        true => route_enum.nested_name(rv),
+                        false => route_enum.name(rv),
+                    };
+                    let guards = guard_names(rv);
+                    let csrf = !rv.csrf_skip;
+                    let fields = field_infos(rv, &pattern);
+                    let doc = doc(rv.attrs);
+                    let body = type_name(rv.body.as_ref());
+                    let response = type_name(rv.response.as_ref());
+                    quote! {
+                        &[enum_router::RouteInfo {
+                            method: #method,
+                            methods: &[#(#methods,)*],
+                            pattern: #pattern,
+                            enum_name: #enum_name,
+                            variant: #variant,
+                            handler: #handler,
+                            name: #name,
+                            guards: &[#(#guards,)*],
+                            csrf: #csrf,
+                            fields: &[#(#fields,)*],
+                            doc: #doc,
+                            body: #body,
+                            response: #response,
+                        }]
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let nested_sources = sources(true);
+    let sources = sources(false);
+
+    let variant_paths = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let path = format!("{}::{}", enum_name, rv.variant);
+            match &rv.attr {
+                Attr::Router(ty) => quote! {
+                    paths.extend(
+                        <#ty>::__variant_paths()
+                            .into_iter()
+                            .map(|path| format!("{}({})", #path, path)),
+                    );
+                },
+                _ => quote! { paths.push(#path.to_owned()); },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let openapi = match cfg!(feature = "openapi") {
+        true => quote! {
+            /// An OpenAPI 3.1 document describing every route.
+            #[allow(unused)]
+            pub fn openapi() -> enum_router::openapi::Value {
+                enum_router::openapi::document(
+                    Self::ROUTES,
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION"),
+                )
+            }
+        },
+        false => quote! {},
+    };
+
+    let typescript = match cfg!(feature = "typescript") {
+        true => quote! {
+            /// A TypeScript module with a url builder for every route.
+            #[allow(unused)]
+            pub fn typescript() -> String {
+                enum_router::typescript::module(Self::ROUTES, false)
+            }
+
+            /// Like `typescript()`, plus a typed `fetch` wrapper for every route.
+            #[allow(unused)]
+            pub fn typescript_client() -> String {
+                enum_router::typescript::module(Self::ROUTES, true)
+            }
+        },
+        false => quote! {},
+    };
+
+    quote! {
+        impl #ident {
+            /// Every route registered by `router()`, including nested routers.
+            pub const ROUTES: &'static [enum_router::RouteInfo] = {
+                const SOURCES: &[&[enum_router::RouteInfo]] = &[#(#sources,)*];
+                &enum_router::RouteInfo::flatten::<{ enum_router::RouteInfo::count(SOURCES) }>(
+                    SOURCES,
+                )
+            };
+
+            /// `ROUTES` as seen from an enum this one is nested in, with the
+            /// names of its own routes prefixed by the enum name.
+            #[doc(hidden)]
+            pub const __NESTED_ROUTES: &'static [enum_router::RouteInfo] = {
+                const SOURCES: &[&[enum_router::RouteInfo]] = &[#(#nested_sources,)*];
+                &enum_router::RouteInfo::flatten::<{ enum_router::RouteInfo::count(SOURCES) }>(
+                    SOURCES,
+                )
+            };
+
+            #[allow(unused)]
+            pub fn pattern(&self) -> &'static str {
+                match self {
+                    #(#patterns,)*
+                }
+            }
+
+            /// Low cardinality name for metrics and logs, e.g. `"sessions.edit"`.
+            #[allow(unused)]
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#names,)*
+                }
+            }
+
+            #[doc(hidden)]
+            #[allow(unused)]
+            pub fn __nested_name(&self) -> &'static str {
+                match self {
+                    #(#nested_names,)*
+                }
+            }
+
+            /// The guards run in front of the handler, e.g. `["is_owner"]`.
+            #[allow(unused)]
+            pub fn guards(&self) -> &'static [&'static str] {
+                match self {
+                    #(#guards,)*
+                }
+            }
+
+            /// A table of every route, e.g. for a dev endpoint or a snapshot test.
+            #[allow(unused)]
+            pub fn routes_table() -> String {
+                enum_router::routes_table(Self::ROUTES, &Self::__variant_paths())
+            }
+
+            #[allow(unused)]
+            pub fn print_routes() {
+                print!("{}", Self::routes_table());
+            }
+
+            #openapi
+            #typescript
+
+            #[doc(hidden)]
+            pub fn __variant_paths() -> Vec<String> {
+                let mut paths = Vec::new();
+                #(#variant_paths)*
+                paths
+            }
+        }
+    }
+}
+
+fn breadcrumbs_impl(route_enum: &RouteEnum) -> TokenStream2 {
+    let ident = route_enum.ident;
+
+    let titles = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let left = left(ident, rv.variant, rv.fields);
+            let right = match (&rv.title, &rv.attr) {
+                (Some(title), _) => quote! { #title },
+                (None, Attr::Router(_)) => quote! { x0.title() },
+                (None, _) => {
+                    let title = match (route_enum.resource, rv.variant.to_string().as_str()) {
+                        (true, "Index") => humanize(&ident.to_string()),
+                        _ => humanize(&rv.variant.to_string()),
+                    };
+                    quote! { #title }
+                }
+            };
+            quote! { #left => #right }
+        })
+        .collect::<Vec<_>>();
+
+    // without `#[parent]`, resource actions are below `Index` and the other
+    // variants of a router are below its `GET /` variant
+    let implicit = route_enum
+        .variants
+        .iter()
+        .find(|rv| match route_enum.resource {
+            true => rv.variant == "Index" && matches!(rv.fields, Fields::Unit),
+            false => {
+                !matches!(rv.attr, Attr::Router(_))
+                    && matches!(rv.fields, Fields::Unit)
+                    && rv.attr.method() == "get"
+                    && route_enum.pattern(rv) == "/"
+            }
+        })
+        .map(|rv| rv.variant);
+
+    let parents = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let variant = rv.variant;
+            let fallback = match (&rv.parent, implicit) {
+                (Some(Parent { expr, .. }), _) => quote! { Some(#expr) },
+                (None, Some(implicit)) if implicit != variant => quote! { Some(Self::#implicit) },
+                (None, _) => quote! { None },
+            };
+            let mut used = rv
+                .parent
+                .as_ref()
+                .map(|parent| parent.fields.clone())
+                .unwrap_or_default();
+            let right = match rv.attr {
+                Attr::Router(_) => {
+                    let nested = Member::Unnamed(0.into());
+                    let x0 = field_binding(&nested);
+                    if !used.contains(&nested) {
+                        used.push(nested);
+                    }
+                    quote! {
+                        match #x0.parent() {
+                            Some(parent) => Some(Self::#variant(parent)),
+                            None => #fallback,
+                        }
+                    }
+                }
+                _ => fallback,
+            };
+            let pattern = parent_pattern(ident, variant, rv.fields, &used);
+            quote! { #pattern => #right }
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl #ident {
+            /// `#[title("...")]`, or the variant name, e.g. `"Edit task"` for `EditTask`.
+            #[allow(unused)]
+            pub fn title(&self) -> &'static str {
+                match self {
+                    #(#titles,)*
+                }
+            }
+
+            /// `#[parent(...)]`, or the route this one is below.
+            #[allow(unused)]
+            pub fn parent(&self) -> Option<Self> {
+                match self {
+                    #(#parents,)*
+                }
+            }
+
+            /// `(title, url)` of every route from the top down to this one.
+            #[allow(unused)]
+            pub fn breadcrumbs(&self) -> Vec<(String, String)> {
+                let mut breadcrumbs = vec![(self.title().to_owned(), self.url().to_string())];
+                let mut parent = self.parent();
+                while let Some(route) = parent {
+                    let breadcrumb = (route.title().to_owned(), route.url().to_string());
+                    // stop at a cycle of parents
+                    if breadcrumbs.contains(&breadcrumb) {
+                        break;
+                    }
+                    breadcrumbs.push(breadcrumb);
+                    parent = route.parent();
+                }
+                breadcrumbs.reverse();
+                breadcrumbs
+            }
+        }
+    }
+}
+
+fn sitemap_impl(route_enum: &RouteEnum) -> TokenStream2 {
+    let ident = route_enum.ident;
+
+    let options = route_enum
+        .variants
+        .iter()
+        .map(|rv| {
+            let left = left(ident, rv.variant, rv.fields);
+            let right = match (&rv.sitemap, &rv.attr) {
+                (Some(sitemap), _) if sitemap.skip => quote! { None },
+                (_, Attr::Router(_)) => quote! { x0.sitemap_options() },
+                (_, attr) if !attr.methods().iter().any(|method| method == "GET") => {
+                    quote! { None }
+                }
+                (sitemap, _) => {
+                    let changefreq = match sitemap.as_ref().and_then(|s| s.changefreq.as_ref()) {
+                        Some(changefreq) => quote! { Some(#changefreq) },
+                        None => quote! { None },
+                    };
+                    let priority = match sitemap.as_ref().and_then(|s| s.priority.as_ref()) {
+                        Some(priority) => quote! { Some(#priority) },
+                        None => quote! { None },
+                    };
+                    quote! {
+                        Some(enum_router::sitemap::Options {
+                            changefreq: #changefreq,
+                            priority: #priority,
+                        })
+                    }
+                }
+            };
+            quote! { #left => #right }
+        })
+        .collect::<Vec<_>>();
+
+    let units = route_enum
+        .variants
+        .iter()
+        .filter_map(|rv| {
+            let variant = rv.variant;
+            match (&rv.attr, rv.fields) {
+                (Attr::Router(ty), _) => Some(quote! {
+                    units.extend(<#ty>::__sitemap_units().into_iter().map(Self::#variant));
+                }),
+                (_, Fields::Unit) => Some(quote! { units.push(Self::#variant); }),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl #ident {
+            /// `#[sitemap(changefreq = "...", priority = ...)]`, `None` for
+            /// `#[sitemap(skip)]` and routes that don't answer `GET`.
+            #[allow(unused)]
+            pub fn sitemap_options(&self) -> Option<enum_router::sitemap::Options> {
+                match self {
+                    #(#options,)*
+                }
+            }
+
+            #[doc(hidden)]
+            pub fn __sitemap_units() -> Vec<Self> {
+                let mut units = Vec::new();
+                #(#units)*
+                units
+            }
+        }
+    }
+}
+
+/// `"EditTask"` to `"Edit task"`.
+fn humanize(input: &str) -> String {
+    let words = pascal_to_snake(input).replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+/// The arguments of `#[cors(origin = "*", headers = "content-type", max_age = 600)]`.
+struct CorsArgs {
+    origin: LitStr,
+    headers: Option<LitStr>,
+    max_age: Option<LitInt>,
+}
+
+impl Parse for CorsArgs {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let mut origin = None;
+        let mut headers = None;
+        let mut max_age = None;
+        while !input.is_empty() {
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "origin" => origin = Some(input.parse()?),
+                "headers" => headers = Some(input.parse()?),
+                "max_age" => max_age = Some(input.parse()?),
+                _ => return Err(syn::Error::new(key.span(), "Unsupported cors arg")),
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(CorsArgs {
+            origin: origin.ok_or_else(|| input.error("cors requires an origin = \"...\""))?,
+            headers,
+            max_age,
+        })
+    }
+}
+
+fn cors(attrs: &[Attribute]) -> Result<Option<CorsArgs>> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cors"))
+        .map(|attr| attr.parse_args::<CorsArgs>())
+        .next_back()
+        .transpose()
+}
+
+/// The arguments of `#[sitemap(skip)]` or `#[sitemap(changefreq = "daily", priority = 0.8)]`.
+struct SitemapArgs {
+    skip: bool,
+    changefreq: Option<LitStr>,
+    priority: Option<LitStr>,
+}
+
+impl Parse for SitemapArgs {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let mut args = SitemapArgs {
+            skip: false,
+            changefreq: None,
+            priority: None,
+        };
+        while !input.is_empty() {
+            let key = input.parse::<Ident>()?;
+            match key.to_string().as_str() {
+                "skip" => args.skip = true,
+                "changefreq" => {
+                    input.parse::<Token![=]>()?;
+                    let changefreq = input.parse::<LitStr>()?;
+                    let valid = [
+                        "always", "hourly", "daily", "weekly", "monthly", "yearly", "never",
+                    ];
+                    if !valid.contains(&changefreq.value().as_str()) {
+                        return Err(syn::Error::new(
+                            changefreq.span(),
+                            format!("Expected one of {}", valid.join(", ")),
+                        ));
+                    }
+                    args.changefreq = Some(changefreq);
+                }
+                "priority" => {
+                    input.parse::<Token![=]>()?;
+                    let priority = input.parse::<LitFloat>()?;
+                    let digits = priority.base10_digits();
+                    if !digits.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+                        || !(0.0..=1.0).contains(&priority.base10_parse::<f32>()?)
+                    {
+                        return Err(syn::Error::new(
+                            priority.span(),
+                            "Expected a priority from 0.0 to 1.0",
+                        ));
+                    }
+                    // the literal's own digits, which `<priority>` repeats as written
+                    args.priority = Some(LitStr::new(digits, priority.span()));
+                }
+                _ => return Err(syn::Error::new(key.span(), "Unsupported sitemap arg")),
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
             }
         }
-    };
 
-    Ok(expanded)
+        Ok(args)
+    }
 }
 
-#[proc_macro_derive(QueryString)]
-pub fn query_string(s: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(s as DeriveInput);
-    match query_string_macro(input) {
-        Ok(s) => s.to_token_stream().into(),
-        Err(e) => e.to_compile_error().into(),
+/// Whether the variant has `#[csrf(skip)]`.
+fn csrf_skip(attrs: &[Attribute]) -> Result<bool> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path.is_ident("csrf")) else {
+        return Ok(false);
+    };
+    let arg = attr.parse_args::<Ident>()?;
+    match arg == "skip" {
+        true => Ok(true),
+        false => Err(syn::Error::new(arg.span(), "Expected #[csrf(skip)]")),
     }
 }
 
-fn query_string_macro(input: DeriveInput) -> Result<TokenStream2> {
-    let struct_name = input.ident;
-    let Data::Struct(data) = input.data else {
-        return Err(syn::Error::new(
-            Span::call_site(),
-            "Only structs are supported",
-        ));
-    };
-    let field_tokens = data
-        .fields
+/// The argument of the last `#[name(...)]` attribute.
+fn parse_attr<T: Parse>(attrs: &[Attribute], name: &str) -> Result<Option<T>> {
+    attrs
         .iter()
-        .filter(|field| field.ident.is_some())
-        .map(|field| {
-            let ident = field.ident.as_ref().unwrap();
-            let name = ident.to_string();
-            quote! { (#name, self.#ident.as_ref()) }
-        })
-        .collect::<Vec<_>>();
+        .filter(|attr| attr.path.is_ident(name))
+        .map(|attr| attr.parse_args::<T>())
+        .next_back()
+        .transpose()
+}
 
-    let tokens = quote! {
-        impl #struct_name {
-            fn query_string(&self) -> String {
-                [#(#field_tokens,)*]
-                    .iter()
-                    .filter(|(_, value)| value.is_some())
-                    .map(|(key, value)| format!("{}={}", key, value.unwrap()))
-                    .collect::<Vec<_>>()
-                    .join("&")
-            }
-        }
+/// A `#[parent(...)]` expression, with the variant's fields written as
+/// `self.0` or `self.name` replaced by bindings of the `parent()` match arm.
+struct Parent {
+    expr: Expr,
+    /// The fields the expression uses.
+    fields: Vec<Member>,
+}
+
+fn parent(attrs: &[Attribute], fields: &Fields) -> Result<Option<Parent>> {
+    let Some(mut expr) = parse_attr::<Expr>(attrs, "parent")? else {
+        return Ok(None);
+    };
+    let mut self_fields = SelfFields {
+        fields,
+        used: Vec::new(),
+        error: None,
     };
+    self_fields.visit_expr_mut(&mut expr);
+    match self_fields.error {
+        Some(error) => Err(error),
+        None => Ok(Some(Parent {
+            expr,
+            fields: self_fields.used,
+        })),
+    }
+}
 
-    Ok(tokens)
+/// Replaces `self.0` and `self.name` with a clone of the field's binding.
+struct SelfFields<'a> {
+    fields: &'a Fields,
+    used: Vec<Member>,
+    error: Option<syn::Error>,
 }
 
-#[proc_macro_derive(
-    Routes,
-    attributes(
-        get, post, delete, patch, put, trace, head, state, resource, query, router
-    )
-)]
-pub fn routes(s: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(s as DeriveInput);
-    match routes_macro(input) {
-        Ok(s) => s.to_token_stream().into(),
-        Err(e) => e.to_compile_error().into(),
+impl VisitMut for SelfFields<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        let member = match expr {
+            Expr::Field(field) if matches!(&*field.base, Expr::Path(path) if path.path.is_ident("self")) => {
+                field.member.clone()
+            }
+            _ => return visit_mut::visit_expr_mut(self, expr),
+        };
+        let exists = match (&member, self.fields) {
+            (Member::Unnamed(index), Fields::Unnamed(fields)) => {
+                (index.index as usize) < fields.unnamed.len()
+            }
+            (Member::Named(name), Fields::Named(fields)) => fields
+                .named
+                .iter()
+                .any(|field| field.ident.as_ref() == Some(name)),
+            _ => false,
+        };
+        if !exists {
+            self.error.get_or_insert(syn::Error::new_spanned(
+                &member,
+                "The variant has no such field",
+            ));
+            return;
+        }
+
+        let binding = field_binding(&member);
+        if !self.used.contains(&member) {
+            self.used.push(member);
+        }
+        *expr = syn::parse_quote! { ::std::clone::Clone::clone(#binding) };
     }
 }
 
-fn routes_macro(input: DeriveInput) -> Result<TokenStream2> {
-    let enum_name = input.ident;
-    let Data::Enum(data) = input.data else {
-        return Err(syn::Error::new(
-            Span::call_site(),
-            "Only enums are supported",
-        ));
+/// The binding of a field in a `parent()` match arm, hygienic so that only
+/// the macro's own `self.0` replacements can use it.
+fn field_binding(member: &Member) -> Ident {
+    let mut binding = match member {
+        Member::Unnamed(index) => format_ident!("x{}", index.index),
+        Member::Named(name) => format_ident!("field_{}", name),
     };
+    binding.set_span(Span::mixed_site());
 
-    let arg = input
-        .attrs
-        .iter()
-        .filter(|attr| match attr.path.get_ident() {
-            Some(ident) => ident.to_string() == "state",
-            None => false,
-        })
-        .filter_map(args)
-        .last();
+    binding
+}
 
-    let state_generic = match arg {
-        Some(Args { state }) => quote! { #state },
-        None => quote! { () },
-    };
+/// The pattern of a `parent()` match arm, binding only the fields in `used`.
+fn parent_pattern(
+    ident: &Ident,
+    variant: &Ident,
+    fields: &Fields,
+    used: &[Member],
+) -> TokenStream2 {
+    match fields {
+        Fields::Unit => quote! { #ident::#variant },
+        Fields::Unnamed(fields) => {
+            let fields = (0..fields.unnamed.len()).map(|i| {
+                let member = Member::Unnamed(i.into());
+                match used.contains(&member) {
+                    true => field_binding(&member).to_token_stream(),
+                    false => quote! { _ },
+                }
+            });
+            quote! { #ident::#variant(#(#fields),*) }
+        }
+        Fields::Named(_) => {
+            let fields = used.iter().map(|member| {
+                let binding = field_binding(member);
+                quote! { #member: #binding }
+            });
+            quote! { #ident::#variant { #(#fields,)* .. } }
+        }
+    }
+}
 
-    let variants = data
-        .variants
+/// The expressions of `#[layer(...)]` attributes.
+fn layers(attrs: &[Attribute]) -> Result<Vec<Expr>> {
+    attrs
         .iter()
-        .map(|variant| RouteVariant::try_from(variant))
-        .collect::<Result<Vec<_>>>()?;
+        .filter(|attr| attr.path.is_ident("layer"))
+        .map(|attr| attr.parse_args::<Expr>())
+        .collect()
+}
 
-    let urls = variants
+fn guard_names(rv: &RouteVariant) -> Vec<String> {
+    rv.guards
         .iter()
-        .map(|rv| {
-            let left = left(&enum_name, rv.variant, rv.fields);
-            let right = right(rv);
+        .map(|guard| quote! { #guard }.to_string().replace(' ', ""))
+        .collect()
+}
 
-            quote! { #left => #right }
-        })
-        .collect::<Vec<_>>();
+fn type_name(ty: Option<&Type>) -> TokenStream2 {
+    match ty {
+        Some(ty) => {
+            let name = quote! { #ty }.to_string().replace(' ', "");
+            quote! { Some(#name) }
+        }
+        None => quote! { None },
+    }
+}
 
-    let methods = variants
+/// Asserts at compile time that handlers declared with `check_body`
+/// take the body as a `Json<T>` or `Form<T>` last argument.
+fn body_checks(route_enum: &RouteEnum) -> TokenStream2 {
+    let checks = route_enum
+        .variants
         .iter()
-        .map(|rv| {
-            let left = left(&enum_name, rv.variant, rv.fields);
-            let right = right(rv);
-
-            quote! { #left => #right.to_owned() }
+        .filter(|rv| rv.check_body)
+        .filter_map(|rv| {
+            let body = rv.body.as_ref()?;
+            let handler = route_enum.handler(rv);
+            Some(quote! { check::<#body, _, _>(#handler); })
         })
         .collect::<Vec<_>>();
 
-    let axum_route = variants
-        .iter()
-        .map(
-            |RouteVariant {
-                 attr,
-                 variant,
-                 path,
-                 ..
-             }| match attr {
-                Attr::Router(ty) => quote! {
-                    .merge(#ty::router())
-                },
-                method => {
-                    let fn_string = pascal_to_snake(&variant.to_string());
-                    let fn_name = Ident::new(&fn_string, variant.span());
-                    let method = Ident::new(&method.to_string(), variant.span());
-                    quote! { .route(#path, #method(#fn_name)) }
-                }
-            },
-        )
-        .collect::<Vec<_>>();
+    if checks.is_empty() {
+        return quote! {};
+    }
 
-    let expanded = quote! {
-        impl #enum_name {
-            pub fn url(&self) -> String {
-                match self {
-                    #(#urls,)*
-                }
+    quote! {
+        const _: () = {
+            trait Body<T> {}
+            impl<T> Body<T> for ::axum::Json<T> {}
+            impl<T> Body<T> for ::axum::Form<T> {}
+
+            fn check<B, F, Args>(_handler: F)
+            where
+                F: enum_router::LastArg<Args>,
+                <F as enum_router::LastArg<Args>>::Last: Body<B>,
+            {
             }
 
             #[allow(unused)]
-            pub fn method(&self) -> String {
-                match self {
-                    #(#methods,)*
-                }
+            fn checks() {
+                #(#checks)*
             }
+        };
+    }
+}
 
-            pub fn router() -> ::axum::Router<#state_generic> {
-                use ::axum::routing::{get, post, patch, put, delete, trace, head};
-                ::axum::Router::new()#(#axum_route)*
-            }
-        }
+fn field_infos(rv: &RouteVariant, pattern: &str) -> Vec<TokenStream2> {
+    let mut params = pattern
+        .split('/')
+        .filter_map(|part| part.strip_prefix('{')?.strip_suffix('}'));
 
-        impl std::fmt::Display for #enum_name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.write_fmt(format_args!("{}", self.url()))
+    rv.fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let query = field.attrs.iter().any(|attr| attr.path.is_ident("query"));
+            let name = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None if query => i.to_string(),
+                None => params
+                    .next()
+                    .map(|param| param.trim_start_matches('*').to_owned())
+                    .unwrap_or_else(|| i.to_string()),
+            };
+            let ty = &field.ty;
+            let fields = match query {
+                true => quote! { <#ty>::QUERY_FIELDS },
+                false => quote! { &[] },
+            };
+            let ty = quote! { #ty }.to_string().replace(' ', "");
+            quote! {
+                enum_router::FieldInfo {
+                    name: #name,
+                    ty: #ty,
+                    query: #query,
+                    fields: #fields,
+                }
             }
-        }
-    };
+        })
+        .collect()
+}
 
-    Ok(expanded)
+fn doc(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(lit),
+                ..
+            })) => Some(lit.value().trim().to_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_owned()
 }
 
-fn right_from_unnamed(
-    RouteVariant { attr, path, .. }: &RouteVariant,
-    fields: &FieldsUnnamed,
-) -> TokenStream2 {
+fn right_from_unnamed(path: &LitStr, fields: &FieldsUnnamed) -> TokenStream2 {
     let is_query = fields
         .unnamed
         .iter()
@@ -303,30 +1573,27 @@ fn right_from_unnamed(
                     if qs.is_empty() {
                         #path.to_string()
                     } else {
-                        format!("{}?{}", #path, x0.query_string())
+                        format!("{}?{}", #path, qs)
                     }
                 }
             }
         }
-        false => match attr {
-            Attr::Router(_ty) => quote! { format!("{}", x0) },
-            _ => {
-                let format = path
-                    .value()
-                    .split('/')
-                    .map(|part| if part.contains("{") { "{}" } else { part })
-                    .collect::<Vec<_>>()
-                    .join("/");
-                let idents = fields
-                    .unnamed
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _field)| Ident::new(&format!("x{}", i), Span::call_site()))
-                    .collect::<Vec<_>>();
+        false => {
+            let format = path
+                .value()
+                .split('/')
+                .map(|part| if part.contains('{') { "{}" } else { part })
+                .collect::<Vec<_>>()
+                .join("/");
+            let idents = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, _field)| Ident::new(&format!("x{}", i), Span::call_site()))
+                .collect::<Vec<_>>();
 
-                quote! { format!(#format, #(#idents,)*) }
-            }
-        },
+            quote! { format!(#format, #(#idents,)*) }
+        }
     }
 }
 
@@ -351,12 +1618,45 @@ fn right_from_named(fields: &FieldsNamed, path: &LitStr) -> TokenStream2 {
 fn right(rv: &RouteVariant) -> TokenStream2 {
     let path = &rv.path;
     match rv.fields {
-        Fields::Named(fields) => right_from_named(&fields, &rv.path),
-        Fields::Unnamed(fields) => right_from_unnamed(&rv, &fields),
+        Fields::Named(fields) => right_from_named(fields, path),
+        Fields::Unnamed(fields) => right_from_unnamed(path, fields),
         Fields::Unit => quote! { #path.to_owned() },
     }
 }
 
+fn url(rv: &RouteVariant, prefix: &str, base_path: Option<&LitStr>) -> TokenStream2 {
+    let configured = match base_path {
+        Some(lit) => quote! { Some(#lit) },
+        None => quote! { None },
+    };
+    match (&rv.attr, base_path) {
+        (Attr::Router(_), None) => quote! { x0.url() },
+        (Attr::Router(_), Some(_)) => {
+            quote! { enum_router::base_path::apply(x0.url(), #configured) }
+        }
+        _ => {
+            let method = method(rv);
+            let right = right(rv);
+            quote! {
+                enum_router::base_path::apply(
+                    enum_router::Href::new(#method, format!("{}{}", #prefix, #right)),
+                    #configured,
+                )
+            }
+        }
+    }
+}
+
+fn method(rv: &RouteVariant) -> TokenStream2 {
+    match rv.attr {
+        Attr::Router(_) => quote! { x0.method() },
+        _ => {
+            let method = Ident::new(&rv.attr.method().to_uppercase(), rv.variant.span());
+            quote! { ::axum::http::Method::#method }
+        }
+    }
+}
+
 fn left_from_named(r#ident: &Ident, variant: &Ident, fields: &FieldsNamed) -> TokenStream2 {
     let idents = fields
         .named
@@ -390,14 +1690,25 @@ fn left(r#ident: &Ident, variant: &Ident, fields: &Fields) -> TokenStream2 {
     }
 }
 
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
 fn pascal_to_snake(input: &str) -> String {
     let mut result = String::with_capacity(input.len());
     let mut chars = input.chars();
-    if let Some(char) = &chars.nth(0) {
+    if let Some(char) = &chars.next() {
         result.push(char.to_ascii_lowercase());
     }
 
-    while let Some(char) = chars.next() {
+    for char in chars {
         if char.is_uppercase() {
             result.push('_');
             result.push(char.to_lowercase().next().unwrap());
@@ -418,14 +1729,285 @@ enum Attr {
     Delete,
     Trace,
     Head,
-    Router(syn::Type),
+    Options,
+    Connect,
+    /// `#[route(GET, HEAD, "/feed")]`, the first method is the variant's `method()`.
+    Methods(Vec<Attr>),
+    /// `#[any("/webhook")]`, the variant's `method()` is `GET`.
+    Any,
+    Router(Box<syn::Type>),
+}
+
+struct RouteEnum<'a> {
+    ident: &'a Ident,
+    variants: Vec<RouteVariant<'a>>,
+    prefix: String,
+    base_path: Option<LitStr>,
+    resource: bool,
+    /// `#[layer(...)]` attributes on the enum, applied to all of its routes.
+    layers: Vec<Expr>,
+    /// `#[cors(...)]` on the enum, for the automatic `OPTIONS` responses.
+    cors: Option<CorsArgs>,
+}
+
+impl RouteEnum<'_> {
+    fn handler(&self, rv: &RouteVariant) -> TokenStream2 {
+        let fn_name = Ident::new(&pascal_to_snake(&rv.variant.to_string()), rv.variant.span());
+        match self.resource {
+            true => {
+                let ident = self.ident;
+                quote! { #ident::#fn_name }
+            }
+            false => quote! { #fn_name },
+        }
+    }
+
+    fn pattern(&self, rv: &RouteVariant) -> String {
+        format!("{}{}", self.prefix, rv.path.value().replace("{{}}", "{id}"))
+    }
+
+    /// `name = "..."`, or the handler name, prefixed with the enum name for resources.
+    fn name(&self, rv: &RouteVariant) -> String {
+        let variant = pascal_to_snake(&rv.variant.to_string());
+        match (&rv.name, self.resource) {
+            (Some(name), _) => name.value(),
+            (None, true) => format!("{}.{}", pascal_to_snake(&self.ident.to_string()), variant),
+            (None, false) => variant,
+        }
+    }
+
+    /// The name of a route variant when the enum is nested in another one,
+    /// prefixed with the enum name like the names of resources already are.
+    fn nested_name(&self, rv: &RouteVariant) -> String {
+        match (&rv.name, self.resource) {
+            (None, false) => format!(
+                "{}.{}",
+                pascal_to_snake(&self.ident.to_string()),
+                pascal_to_snake(&rv.variant.to_string())
+            ),
+            _ => self.name(rv),
+        }
+    }
+
+    /// The method router for a route variant, which inserts the
+    /// variant's `MatchedRoute` before its layers, guards and the handler run.
+    fn method_router(&self, rv: &RouteVariant) -> TokenStream2 {
+        let handler = rv
+            .guards
+            .iter()
+            .rev()
+            .fold(self.handler(rv), |handler, guard| {
+                quote! { enum_router::Guarded::new(#guard, #handler) }
+            });
+        let method_router = rv.attr.method_router(&handler, rv.variant.span());
+        let index = self.route_index(rv);
+        self.layered(rv, method_router, quote! { &Self::ROUTES[#index] })
+    }
+
+    /// `method_router` with the layers of a route variant, inside the
+    /// `MatchedRoute` of `route`, an expression of the `&'static RouteInfo`
+    /// it is registered for.
+    fn layered(
+        &self,
+        rv: &RouteVariant,
+        method_router: TokenStream2,
+        route: TokenStream2,
+    ) -> TokenStream2 {
+        let layers = self.layers(rv).collect::<Vec<_>>();
+        quote! {{
+            let method_router = #method_router;
+            let route: &'static enum_router::RouteInfo = #route;
+            let matched_route = ::axum::middleware::from_fn_with_state(
+                enum_router::MatchedRoute(route),
+                enum_router::insert_matched_route,
+            );
+            // `any` routes are the fallback of their method router, which `route_layer` skips
+            match route.methods == ["*"] {
+                true => method_router #(.layer(#layers))* .layer(matched_route),
+                false => method_router #(.route_layer(#layers))* .route_layer(matched_route),
+            }
+        }}
+    }
+
+    /// The enum's `#[cors(...)]`, for the `OPTIONS` responses of its routes.
+    fn cors(&self) -> TokenStream2 {
+        match &self.cors {
+            Some(CorsArgs {
+                origin,
+                headers,
+                max_age,
+            }) => {
+                let headers = headers.as_ref().map(LitStr::value).unwrap_or_default();
+                let max_age = match max_age {
+                    Some(max_age) => quote! { Some(#max_age) },
+                    None => quote! { None },
+                };
+                quote! {
+                    Some(enum_router::Cors {
+                        origin: #origin,
+                        headers: #headers,
+                        max_age: #max_age,
+                    })
+                }
+            }
+            None => quote! { None },
+        }
+    }
+
+    /// `__method_routers()`, the method router of every route in `ROUTES`,
+    /// which `router()` registers at the route's pattern.
+    ///
+    /// The routes of a `#[router]` variant get the variant's and the enum's
+    /// layers, inside a `MatchedRoute` inserted before them, so layers of
+    /// every enum see the route.
+    fn method_routers(&self, state: &TokenStream2) -> TokenStream2 {
+        let cors = self.cors();
+        let pushes = self.variants.iter().map(|rv| match &rv.attr {
+            Attr::Router(ty) => {
+                let index = self.route_index(rv);
+                let method_router = self.layered(
+                    rv,
+                    quote! { method_router },
+                    quote! { &Self::ROUTES[#index + i] },
+                );
+                quote! {
+                    method_routers.extend(<#ty>::__method_routers().into_iter().enumerate().map(
+                        |(i, (method_router, cors))| (#method_router, cors),
+                    ));
+                }
+            }
+            _ => {
+                let method_router = self.method_router(rv);
+                quote! { method_routers.push((#method_router, #cors)); }
+            }
+        });
+
+        quote! {
+            #[doc(hidden)]
+            pub fn __method_routers(
+            ) -> Vec<(::axum::routing::MethodRouter<#state>, Option<enum_router::Cors>)> {
+                let mut method_routers = Vec::new();
+                #(#pushes)*
+                method_routers
+            }
+        }
+    }
+
+    /// The layers of a route variant, innermost first, so the first
+    /// `#[layer(...)]` runs first and the enum's layers run before the variant's.
+    fn layers<'b>(&'b self, rv: &'b RouteVariant) -> impl Iterator<Item = &'b Expr> {
+        rv.layers.iter().rev().chain(self.layers.iter().rev())
+    }
+
+    /// The index of a route variant in `ROUTES`, after the routes of the
+    /// variants before it.
+    fn route_index(&self, rv: &RouteVariant) -> TokenStream2 {
+        let before = self
+            .variants
+            .iter()
+            .take_while(|other| !std::ptr::eq(*other, rv))
+            .map(|other| match &other.attr {
+                Attr::Router(ty) => quote! { <#ty>::ROUTES.len() },
+                _ => quote! { 1 },
+            });
+
+        quote! { 0 #(+ #before)* }
+    }
+}
+
+/// The arguments of a method attribute, e.g.
+/// `#[post("/todos", body = NewTodo, response = Todo, check_body, name = "new_todo", guard = is_owner)]`.
+struct RouteArgs {
+    path: LitStr,
+    body: Option<Type>,
+    response: Option<Type>,
+    check_body: bool,
+    name: Option<LitStr>,
+    guards: Vec<Expr>,
+}
+
+impl RouteArgs {
+    fn new(path: &str, variant: &Ident) -> Self {
+        Self {
+            path: LitStr::new(path, variant.span()),
+            body: None,
+            response: None,
+            check_body: false,
+            name: None,
+            guards: Vec::new(),
+        }
+    }
+}
+
+impl Parse for RouteArgs {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let path = input.parse::<LitStr>()?;
+        let mut args = RouteArgs {
+            path,
+            body: None,
+            response: None,
+            check_body: false,
+            name: None,
+            guards: Vec::new(),
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key = input.parse::<Ident>()?;
+            match key.to_string().as_str() {
+                "check_body" => args.check_body = true,
+                "body" => {
+                    input.parse::<Token![=]>()?;
+                    args.body = Some(input.parse()?);
+                }
+                "response" => {
+                    input.parse::<Token![=]>()?;
+                    args.response = Some(input.parse()?);
+                }
+                "name" => {
+                    input.parse::<Token![=]>()?;
+                    args.name = Some(input.parse()?);
+                }
+                "guard" => {
+                    input.parse::<Token![=]>()?;
+                    args.guards.push(input.parse()?);
+                }
+                _ => return Err(syn::Error::new(key.span(), "Unsupported route arg")),
+            }
+        }
+        if args.check_body && args.body.is_none() {
+            return Err(syn::Error::new(
+                args.path.span(),
+                "check_body requires a body = Type",
+            ));
+        }
+
+        Ok(args)
+    }
 }
 
 struct RouteVariant<'a> {
     attr: Attr,
     path: LitStr,
+    body: Option<Type>,
+    response: Option<Type>,
+    check_body: bool,
+    name: Option<LitStr>,
+    guards: Vec<Expr>,
+    layers: Vec<Expr>,
+    /// `#[csrf(skip)]`
+    csrf_skip: bool,
+    /// `#[parent(Route::Projects)]`
+    parent: Option<Parent>,
+    /// `#[title("Edit task")]`
+    title: Option<LitStr>,
+    /// `#[sitemap(skip)]` or `#[sitemap(changefreq = "daily", priority = 0.8)]`
+    sitemap: Option<SitemapArgs>,
     variant: &'a Ident,
     fields: &'a Fields,
+    attrs: &'a [Attribute],
 }
 
 impl<'a> TryFrom<&'a Variant> for RouteVariant<'a> {
@@ -434,53 +2016,88 @@ impl<'a> TryFrom<&'a Variant> for RouteVariant<'a> {
     fn try_from(value: &'a Variant) -> std::result::Result<Self, Self::Error> {
         let variant = &value.ident;
 
-        let (attr, path) = if value.attrs.is_empty() {
-            match variant.to_string().as_str() {
-                "Index" => (Attr::Get, LitStr::new("", variant.span())),
-                "Create" => (Attr::Post, LitStr::new("", variant.span())),
-                "New" => (Attr::Get, LitStr::new("/new", variant.span())),
-                "Show" => (Attr::Get, LitStr::new("/{{}}", variant.span())),
-                "Edit" => (Attr::Get, LitStr::new("/{{}}/edit", variant.span())),
-                "Update" => (Attr::Patch, LitStr::new("/{{}}", variant.span())),
-                "Delete" => (Attr::Delete, LitStr::new("/{{}}", variant.span())),
-                _ => todo!(),
-            }
-        } else {
-            value
-                .attrs
-                .iter()
-                .filter_map(|attr| {
-                    let ident = attr.path.get_ident();
-                    let lit_str = attr.parse_args::<LitStr>().ok();
-                    match (ident, lit_str) {
-                        (None, None) => None,
-                        (None, Some(_)) => None,
-                        (Some(_), None) => match &value.fields {
-                            Fields::Unnamed(fields) => match fields.unnamed.first() {
-                                Some(field) => Some((
-                                    Attr::Router(field.ty.clone()),
-                                    LitStr::new(
-                                        &format!("/{}", variant.to_string()),
-                                        variant.span(),
-                                    ),
-                                )),
-                                None => None,
-                            },
-                            _ => None,
-                        },
-                        (Some(ident), Some(lit_str)) => Some((Attr::from(ident), lit_str)),
-                    }
-                })
-                .nth(0)
-                .ok_or(syn::Error::new(variant.span(), "Unsupported attr"))?
+        let route = value
+            .attrs
+            .iter()
+            .find_map(|attr| {
+                let ident = attr.path.get_ident()?;
+                match ident.to_string().as_str() {
+                    "router" => match &value.fields {
+                        Fields::Unnamed(fields) => fields.unnamed.first().map(|field| {
+                            Ok((
+                                Attr::Router(Box::new(field.ty.clone())),
+                                RouteArgs::new(&format!("/{}", variant), variant),
+                            ))
+                        }),
+                        _ => None,
+                    },
+                    "get" | "post" | "put" | "patch" | "delete" | "head" | "trace" | "options"
+                    | "connect" => Some(
+                        attr.parse_args::<RouteArgs>()
+                            .map(|args| (Attr::from(ident), args)),
+                    ),
+                    "any" => Some(attr.parse_args::<RouteArgs>().map(|args| (Attr::Any, args))),
+                    "route" => Some(attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        let mut methods = Vec::new();
+                        while input.peek(Ident) {
+                            let method = input.parse::<Ident>()?;
+                            match Attr::from(&Ident::new(
+                                &method.to_string().to_lowercase(),
+                                method.span(),
+                            )) {
+                                Attr::Router(_) | Attr::Methods(_) | Attr::Any => {
+                                    return Err(syn::Error::new(
+                                        method.span(),
+                                        "Unsupported method",
+                                    ))
+                                }
+                                attr => methods.push(attr),
+                            }
+                            input.parse::<Token![,]>()?;
+                        }
+                        if methods.is_empty() {
+                            return Err(input.error("Expected methods, e.g. GET, HEAD"));
+                        }
+                        let args = input.parse::<RouteArgs>()?;
+
+                        Ok((Attr::Methods(methods), args))
+                    })),
+                    _ => None,
+                }
+            })
+            .transpose()?;
+
+        let (attr, args) = match route {
+            Some(route) => route,
+            None => match variant.to_string().as_str() {
+                "Index" => (Attr::Get, RouteArgs::new("", variant)),
+                "Create" => (Attr::Post, RouteArgs::new("", variant)),
+                "New" => (Attr::Get, RouteArgs::new("/new", variant)),
+                "Show" => (Attr::Get, RouteArgs::new("/{{}}", variant)),
+                "Edit" => (Attr::Get, RouteArgs::new("/{{}}/edit", variant)),
+                "Update" => (Attr::Patch, RouteArgs::new("/{{}}", variant)),
+                "Delete" => (Attr::Delete, RouteArgs::new("/{{}}", variant)),
+                _ => return Err(syn::Error::new(variant.span(), "Unsupported attr")),
+            },
         };
         let fields = &value.fields;
 
         Ok(RouteVariant {
             attr,
-            path,
+            path: args.path,
+            body: args.body,
+            response: args.response,
+            check_body: args.check_body,
+            name: args.name,
+            guards: args.guards,
+            layers: layers(&value.attrs)?,
+            csrf_skip: csrf_skip(&value.attrs)?,
+            parent: parent(&value.attrs, &value.fields)?,
+            title: parse_attr(&value.attrs, "title")?,
+            sitemap: parse_attr(&value.attrs, "sitemap")?,
             variant,
             fields,
+            attrs: &value.attrs,
         })
     }
 }
@@ -495,20 +2112,23 @@ impl From<&Ident> for Attr {
             "delete" => Attr::Delete,
             "head" => Attr::Head,
             "trace" => Attr::Trace,
-            _ => Attr::Router(syn::Type::Path(syn::TypePath {
+            "options" => Attr::Options,
+            "connect" => Attr::Connect,
+            _ => Attr::Router(Box::new(syn::Type::Path(syn::TypePath {
                 qself: None,
                 path: syn::Path {
                     leading_colon: None,
                     segments: syn::punctuated::Punctuated::default(),
                 },
-            })),
+            }))),
         }
     }
 }
 
-impl core::fmt::Display for Attr {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
+impl Attr {
+    /// The method of the variant's `url()` and `method()`, e.g. `"get"`.
+    fn method(&self) -> &'static str {
+        match self {
             Attr::Get => "get",
             Attr::Post => "post",
             Attr::Put => "put",
@@ -516,8 +2136,42 @@ impl core::fmt::Display for Attr {
             Attr::Delete => "delete",
             Attr::Trace => "trace",
             Attr::Head => "head",
+            Attr::Options => "options",
+            Attr::Connect => "connect",
+            Attr::Methods(methods) => methods[0].method(),
+            Attr::Any => "get",
             Attr::Router(_ty) => "",
-        })
+        }
+    }
+
+    /// Every method the route accepts, e.g. `["GET", "HEAD"]`, or `["*"]` for `any`.
+    fn methods(&self) -> Vec<String> {
+        match self {
+            Attr::Methods(methods) => methods
+                .iter()
+                .map(|method| method.method().to_uppercase())
+                .collect(),
+            Attr::Any => vec!["*".to_owned()],
+            attr => vec![attr.method().to_uppercase()],
+        }
+    }
+
+    /// The `MethodRouter` for `handler`, e.g. `get(handler).head(handler)`.
+    fn method_router(&self, handler: &TokenStream2, span: Span) -> TokenStream2 {
+        match self {
+            Attr::Methods(methods) => {
+                let first = Ident::new(methods[0].method(), span);
+                let rest = methods[1..]
+                    .iter()
+                    .map(|method| Ident::new(method.method(), span));
+                quote! { ::axum::routing::#first(#handler)#(.#rest(#handler))* }
+            }
+            Attr::Any => quote! { ::axum::routing::any(#handler) },
+            attr => {
+                let method = Ident::new(attr.method(), span);
+                quote! { ::axum::routing::#method(#handler) }
+            }
+        }
     }
 }
 
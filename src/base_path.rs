@@ -0,0 +1,121 @@
+//! Mount prefix support for apps served below a sub-path, e.g. behind a
+//! reverse proxy at `/app`.
+//!
+//! Generated urls are prefixed with the first base path found in this order:
+//! the current request (see [`forwarded_prefix`]), the enum's own
+//! `#[router(base_path = "/app")]`, then the global [`set`] value.
+//! `Route::router()` keeps matching the stripped path.
+
+use crate::Href;
+use axum::{extract::Request, middleware::Next, response::Response};
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    sync::RwLock,
+    task::{Context, Poll},
+};
+
+static GLOBAL: RwLock<Option<String>> = RwLock::new(None);
+
+thread_local! {
+    static SCOPED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Sets the base path for every route enum in the app.
+pub fn set(base_path: impl AsRef<str>) {
+    let mut global = GLOBAL.write().unwrap_or_else(|e| e.into_inner());
+    *global = normalize(base_path.as_ref());
+}
+
+pub fn clear() {
+    let mut global = GLOBAL.write().unwrap_or_else(|e| e.into_inner());
+    *global = None;
+}
+
+/// The base path urls are currently generated with.
+pub fn current(configured: Option<&str>) -> Option<String> {
+    SCOPED
+        .with(|scoped| scoped.borrow().clone())
+        .or_else(|| configured.and_then(normalize))
+        .or_else(|| GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone())
+}
+
+#[doc(hidden)]
+pub fn apply(href: Href, configured: Option<&str>) -> Href {
+    match current(configured) {
+        Some(base_path) => href.with_base_path(base_path),
+        None => href,
+    }
+}
+
+/// Runs `future` with `base_path` applied to every url generated while it is polled.
+///
+/// The base path does not follow tasks spawned from inside the future.
+pub fn scope<F: Future>(base_path: impl AsRef<str>, future: F) -> impl Future<Output = F::Output> {
+    Scope {
+        base_path: normalize(base_path.as_ref()),
+        future: Box::pin(future),
+    }
+}
+
+/// Middleware that reads the `X-Forwarded-Prefix` header and applies it as the
+/// base path while the rest of the request is handled.
+///
+/// Prefixes with anything other than letters, digits, `-`, `.`, `_`, `~` and
+/// `/` between segments are ignored.
+///
+/// ```rust,ignore
+/// let app = Route::router().layer(axum::middleware::from_fn(enum_router::base_path::forwarded_prefix));
+/// ```
+pub async fn forwarded_prefix(request: Request, next: Next) -> Response {
+    let prefix = request
+        .headers()
+        .get("x-forwarded-prefix")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+
+    match prefix {
+        Some(prefix) => scope(prefix, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
+
+/// `"app/"` to `"/app"`, `None` when empty or when it isn't a plain path.
+///
+/// Only unreserved characters are allowed in segments, so a client supplied
+/// prefix like `\evil.com` or `//evil.com` can't turn urls into links to
+/// another host.
+pub(crate) fn normalize(base_path: &str) -> Option<String> {
+    let base_path = base_path.trim().trim_matches('/');
+    let valid = base_path.split('/').all(|segment| {
+        !segment.is_empty()
+            && segment != "."
+            && segment != ".."
+            && segment
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'))
+    });
+    match valid {
+        true => Some(format!("/{}", base_path)),
+        false => None,
+    }
+}
+
+struct Scope<F> {
+    base_path: Option<String>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scope<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let base_path = self.base_path.clone();
+        let previous = SCOPED.with(|scoped| scoped.replace(base_path));
+        let poll = self.future.as_mut().poll(cx);
+        SCOPED.with(|scoped| scoped.replace(previous));
+
+        poll
+    }
+}
@@ -0,0 +1,33 @@
+/// The last argument of a handler, used by the generated `check_body` assertions.
+#[doc(hidden)]
+pub trait LastArg<Args> {
+    type Last;
+}
+
+macro_rules! impl_last_arg {
+    ($($ty:ident),*; $last:ident) => {
+        impl<F, R, $($ty,)* $last> LastArg<($($ty,)* $last,)> for F
+        where
+            F: FnOnce($($ty,)* $last) -> R,
+        {
+            type Last = $last;
+        }
+    };
+}
+
+impl_last_arg!(; T1);
+impl_last_arg!(T1; T2);
+impl_last_arg!(T1, T2; T3);
+impl_last_arg!(T1, T2, T3; T4);
+impl_last_arg!(T1, T2, T3, T4; T5);
+impl_last_arg!(T1, T2, T3, T4, T5; T6);
+impl_last_arg!(T1, T2, T3, T4, T5, T6; T7);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7; T8);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8; T9);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8, T9; T10);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10; T11);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11; T12);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12; T13);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13; T14);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14; T15);
+impl_last_arg!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15; T16);
@@ -0,0 +1,171 @@
+//! A typed client that calls route variants over any `tower::Service`,
+//! e.g. an axum `Router` in-process or a hyper connection.
+
+use crate::{BaseUrl, Routable};
+use axum::body::Body;
+use http::{
+    header::{self, HeaderName, HeaderValue},
+    uri::InvalidUri,
+    HeaderMap, Request, Uri,
+};
+use serde::Serialize;
+use std::{
+    any::{type_name, TypeId},
+    fmt,
+};
+use tower::{Service, ServiceExt};
+
+#[derive(Clone, Debug)]
+pub struct Client<S> {
+    service: S,
+    base: Option<BaseUrl>,
+    headers: HeaderMap,
+}
+
+impl<S> Client<S> {
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            base: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Sends absolute urls, for services that need a scheme and authority.
+    pub fn base(mut self, base: BaseUrl) -> Self {
+        self.base = Some(base);
+        self
+    }
+
+    /// A header sent with every request.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    fn request<E>(
+        &self,
+        route: &impl Routable,
+        body: Body,
+        content_type: Option<&'static str>,
+    ) -> Result<Request<Body>, ClientError<E>> {
+        // an in-process router matches paths without the base path
+        let uri = match &self.base {
+            Some(base) => base.url(route).to_string(),
+            None => route.url().router_uri(),
+        };
+        let uri = Uri::try_from(&uri).map_err(ClientError::Uri)?;
+        let mut request = Request::builder()
+            .method(route.method())
+            .uri(uri)
+            .body(body)
+            .expect("method and uri are valid");
+        request.headers_mut().extend(self.headers.clone());
+        if let Some(content_type) = content_type {
+            request
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+
+        Ok(request)
+    }
+}
+
+impl<S> Client<S>
+where
+    S: Service<Request<Body>> + Clone,
+{
+    /// Calls `route` with its method and url and an empty body.
+    pub async fn call(&self, route: impl Routable) -> Result<S::Response, ClientError<S::Error>> {
+        let request = self.request(&route, Body::empty(), None)?;
+        self.service
+            .clone()
+            .oneshot(request)
+            .await
+            .map_err(ClientError::Service)
+    }
+
+    /// Calls `route` with `body` serialized as json, for `Json<T>` handlers.
+    ///
+    /// When the variant declares `body = Type`, `body` has to be that type,
+    /// otherwise the call fails with `ClientError::BodyType` before sending.
+    /// Variants without a declared body accept any `Serialize` body.
+    pub async fn call_with<T: Serialize + 'static>(
+        &self,
+        route: impl Routable,
+        body: &T,
+    ) -> Result<S::Response, ClientError<S::Error>> {
+        check_body_type::<T, _>(&route)?;
+        let body = serde_json::to_vec(body).map_err(ClientError::Body)?;
+        let request = self.request(&route, Body::from(body), Some("application/json"))?;
+        self.service
+            .clone()
+            .oneshot(request)
+            .await
+            .map_err(ClientError::Service)
+    }
+
+    /// Calls `route` with `body` serialized as a urlencoded form, for
+    /// `Form<T>` handlers. The body is checked like in `call_with`.
+    pub async fn call_form<T: Serialize + 'static>(
+        &self,
+        route: impl Routable,
+        body: &T,
+    ) -> Result<S::Response, ClientError<S::Error>> {
+        check_body_type::<T, _>(&route)?;
+        let body = serde_urlencoded::to_string(body).map_err(ClientError::Form)?;
+        let request = self.request(
+            &route,
+            Body::from(body),
+            Some("application/x-www-form-urlencoded"),
+        )?;
+        self.service
+            .clone()
+            .oneshot(request)
+            .await
+            .map_err(ClientError::Service)
+    }
+}
+
+/// Fails when the variant declares a `body = Type` other than `T`.
+fn check_body_type<T: 'static, E>(route: &impl Routable) -> Result<(), ClientError<E>> {
+    match route.__body_type() {
+        Some((expected, name)) if expected != TypeId::of::<T>() => Err(ClientError::BodyType {
+            expected: name,
+            found: type_name::<T>(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientError<E> {
+    /// The route's url isn't a valid uri.
+    Uri(InvalidUri),
+    /// The body isn't the variant's declared `body = Type`.
+    BodyType {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The body couldn't be serialized as json.
+    Body(serde_json::Error),
+    /// The body couldn't be serialized as a form.
+    Form(serde_urlencoded::ser::Error),
+    Service(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ClientError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Uri(e) => write!(f, "invalid uri: {}", e),
+            ClientError::BodyType { expected, found } => {
+                write!(f, "expected a {} body, got {}", expected, found)
+            }
+            ClientError::Body(e) => write!(f, "could not serialize body: {}", e),
+            ClientError::Form(e) => write!(f, "could not serialize form: {}", e),
+            ClientError::Service(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ClientError<E> {}
@@ -0,0 +1,157 @@
+//! Records which routes of a route enum were hit, e.g. across a test suite,
+//! and reports the ones that never were.
+//!
+//! ```rust,ignore
+//! static COVERAGE: Coverage = Coverage::new(Route::ROUTES);
+//!
+//! let app = Route::router().layer(axum::middleware::from_fn_with_state(
+//!     &COVERAGE,
+//!     enum_router::coverage::track,
+//! ));
+//! // ...
+//! COVERAGE.assert_all_hit();
+//! ```
+
+use crate::{routes_table, MatchedRoute, RouteInfo};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use http::Method;
+use std::sync::Mutex;
+
+/// Hit counts for every route in `routes`.
+#[derive(Debug)]
+pub struct Coverage {
+    routes: &'static [RouteInfo],
+    hits: Mutex<Vec<u64>>,
+}
+
+impl Coverage {
+    pub const fn new(routes: &'static [RouteInfo]) -> Self {
+        Self {
+            routes,
+            hits: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Counts a hit for the route matching `method` and `pattern`.
+    ///
+    /// `HEAD` requests count for the `GET` route when there is no `HEAD` route.
+    /// Returns the route, or `None` if no route matches.
+    pub fn record(&self, method: &Method, pattern: &str) -> Option<&'static RouteInfo> {
+        let position = |method: &Method| {
+            self.routes.iter().position(|route| {
+                route.pattern == pattern
+                    && route
+                        .methods
+                        .iter()
+                        .any(|m| *m == "*" || *m == method.as_str())
+            })
+        };
+        let index = match position(method) {
+            None if method == Method::HEAD => position(&Method::GET),
+            index => index,
+        }?;
+
+        Some(self.hit_at(index))
+    }
+
+    /// Counts a hit for `route`, the `MatchedRoute` of a request.
+    ///
+    /// Returns the route, or `None` if it isn't one of the tracked routes.
+    pub fn record_route(&self, route: &RouteInfo) -> Option<&'static RouteInfo> {
+        let index = self.routes.iter().position(|r| r == route)?;
+
+        Some(self.hit_at(index))
+    }
+
+    fn hit_at(&self, index: usize) -> &'static RouteInfo {
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        if hits.len() < self.routes.len() {
+            hits.resize(self.routes.len(), 0);
+        }
+        hits[index] += 1;
+
+        &self.routes[index]
+    }
+
+    /// Every route with the number of times it was hit.
+    pub fn hits(&self) -> Vec<(&'static RouteInfo, u64)> {
+        let hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        self.routes
+            .iter()
+            .enumerate()
+            .map(|(i, route)| (route, hits.get(i).copied().unwrap_or(0)))
+            .collect()
+    }
+
+    /// The routes that were hit at least once.
+    pub fn hit(&self) -> Vec<&'static RouteInfo> {
+        self.hits()
+            .into_iter()
+            .filter(|(_, hits)| *hits > 0)
+            .map(|(route, _)| route)
+            .collect()
+    }
+
+    /// The routes that were never hit.
+    pub fn unhit(&self) -> Vec<&'static RouteInfo> {
+        self.hits()
+            .into_iter()
+            .filter(|(_, hits)| *hits == 0)
+            .map(|(route, _)| route)
+            .collect()
+    }
+
+    /// Clears every hit count.
+    pub fn reset(&self) {
+        self.hits.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// `hit/total routes hit`, followed by a table of the routes that were never hit.
+    pub fn report(&self) -> String {
+        let unhit = self.unhit();
+        let mut report = format!(
+            "{}/{} routes hit",
+            self.routes.len() - unhit.len(),
+            self.routes.len()
+        );
+        if !unhit.is_empty() {
+            let variants = unhit
+                .iter()
+                .map(|route| format!("{}::{}", route.enum_name, route.variant))
+                .collect::<Vec<_>>();
+            let unhit = unhit.into_iter().copied().collect::<Vec<_>>();
+            report.push_str("\n\n");
+            report.push_str(&routes_table(&unhit, &variants));
+        }
+
+        report
+    }
+
+    /// Panics with the report if a route was never hit.
+    pub fn assert_all_hit(&self) {
+        if !self.unhit().is_empty() {
+            panic!("{}", self.report());
+        }
+    }
+}
+
+/// Middleware that records the `MatchedRoute` of every response in `coverage`.
+///
+/// Works with `Router::layer` and `Router::route_layer`, and under
+/// `Router::nest` since the route comes from the route enum, not the path.
+pub async fn track(
+    State(coverage): State<&'static Coverage>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    if let Some(MatchedRoute(route)) = response.extensions().get::<MatchedRoute>() {
+        coverage.record_route(route);
+    }
+
+    response
+}
@@ -0,0 +1,256 @@
+//! CSRF protection for the non-GET routes of a route enum, with signed
+//! double-submit cookies.
+//!
+//! ```rust,ignore
+//! let csrf = Csrf::new(secret, Route::ROUTES);
+//! let app = Route::router().layer(axum::middleware::from_fn_with_state(csrf, enum_router::csrf::protect));
+//!
+//! async fn new_todo(token: CsrfToken) -> Html<String> {
+//!     Html(enum_router::html::form_open(&Route::CreateTodo, Some(token.as_str())))
+//! }
+//! ```
+//!
+//! `protect` keeps a signed token in the `__Host-csrf_token` cookie, or
+//! `csrf_token` without `Secure`. Every request with a method other than
+//! `GET`, `HEAD`, `OPTIONS` or `TRACE` must send the same token in the
+//! `x-csrf-token` header or the `csrf_token` form field, otherwise it gets a
+//! `403`. The only exception are requests whose `MatchedPath` is the pattern
+//! of a `#[csrf(skip)]` route of `ROUTES`, so `skip` needs the enum's router
+//! to be the app's router, or merged into it: under a prefix with
+//! `Router::nest` the skipped routes still need a token.
+
+use crate::{
+    form_method::{form_field, DEFAULT_BODY_LIMIT},
+    RouteInfo,
+};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_core::extract::FromRequestParts;
+use hmac::{Hmac, Mac};
+use http::{header, request::Parts, HeaderMap, HeaderValue, Method, StatusCode};
+use sha2::Sha256;
+use std::{fmt, sync::Arc};
+use subtle::ConstantTimeEq;
+
+const COOKIE: &str = "csrf_token";
+const SECURE_COOKIE: &str = "__Host-csrf_token";
+const HEADER: &str = "x-csrf-token";
+const FIELD: &str = "csrf_token";
+
+/// Signs and verifies tokens with a secret, and knows which routes need one.
+#[derive(Clone)]
+pub struct Csrf {
+    secret: Arc<[u8]>,
+    routes: &'static [RouteInfo],
+    secure: bool,
+}
+
+impl fmt::Debug for Csrf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Csrf").finish_non_exhaustive()
+    }
+}
+
+impl Csrf {
+    /// `secret` should be random, at least 32 bytes, and the same on every
+    /// instance of the app.
+    pub fn new(secret: impl AsRef<[u8]>, routes: &'static [RouteInfo]) -> Self {
+        Self {
+            secret: secret.as_ref().into(),
+            routes,
+            secure: true,
+        }
+    }
+
+    /// Whether the cookie has the `Secure` attribute and the `__Host-` name
+    /// prefix, true by default. Turn it off to use the cookie over plain
+    /// http, e.g. in development.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// A new signed token, a random nonce and its HMAC-SHA256.
+    pub fn token(&self) -> CsrfToken {
+        let mut nonce = [0u8; 32];
+        getrandom::getrandom(&mut nonce).expect("the system random number generator failed");
+        let nonce = hex(&nonce);
+        let signature = self.sign(&nonce);
+
+        CsrfToken(format!("{}.{}", nonce, signature))
+    }
+
+    /// Whether `token` was signed with this secret.
+    pub fn verify(&self, token: &str) -> bool {
+        match token.split_once('.') {
+            Some((nonce, signature)) => self
+                .sign(nonce)
+                .as_bytes()
+                .ct_eq(signature.as_bytes())
+                .into(),
+            None => false,
+        }
+    }
+
+    fn cookie_name(&self) -> &'static str {
+        match self.secure {
+            true => SECURE_COOKIE,
+            false => COOKIE,
+        }
+    }
+
+    fn sign(&self, nonce: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("hmac takes keys of any length");
+        mac.update(nonce.as_bytes());
+
+        hex(&mac.finalize().into_bytes())
+    }
+
+    /// Whether `request` needs a token, which is every request with an unsafe
+    /// method unless its `MatchedPath` is a `#[csrf(skip)]` route.
+    ///
+    /// `protect` runs outside the routes, before their `MatchedRoute` is
+    /// inserted, so only axum's `MatchedPath` is there to look the route up.
+    fn requires_token(&self, request: &Request) -> bool {
+        let method = request.method();
+        if matches!(
+            *method,
+            Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+        ) {
+            return false;
+        }
+
+        let skipped = match request.extensions().get::<MatchedPath>() {
+            Some(path) => self.routes.iter().any(|route| {
+                route.pattern == path.as_str()
+                    && !route.csrf
+                    && route
+                        .methods
+                        .iter()
+                        .any(|m| *m == "*" || *m == method.as_str())
+            }),
+            None => false,
+        };
+
+        !skipped
+    }
+}
+
+/// The request's csrf token, for a hidden form field or a meta tag.
+///
+/// Inserted by `protect`, which also sets it as the cookie.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsrfToken(String);
+
+impl CsrfToken {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// `<input type="hidden" name="csrf_token" value="...">`.
+    pub fn hidden_input(&self) -> String {
+        format!(
+            r#"<input type="hidden" name="{}" value="{}">"#,
+            FIELD, self.0
+        )
+    }
+}
+
+impl fmt::Display for CsrfToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<S> FromRequestParts<S> for CsrfToken
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<CsrfToken>().cloned().ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "No CsrfToken, add the csrf::protect middleware",
+        ))
+    }
+}
+
+/// Middleware that checks the token of requests to protected routes and keeps
+/// a token in the `__Host-csrf_token` cookie, or `csrf_token` without `Secure`.
+///
+/// Add it with `Router::layer`, so it runs after routing.
+pub async fn protect(State(csrf): State<Csrf>, request: Request, next: Next) -> Response {
+    let cookie = cookie(request.headers(), csrf.cookie_name()).filter(|token| csrf.verify(token));
+    let required = csrf.requires_token(&request);
+
+    let mut request = request;
+    if required {
+        let header = request
+            .headers()
+            .get(HEADER)
+            .map(|value| value.as_bytes().to_vec());
+        let submitted = match header {
+            Some(token) => Some(token),
+            None => match form_field(request, FIELD, DEFAULT_BODY_LIMIT).await {
+                Ok((form_request, token)) => {
+                    request = form_request;
+                    token
+                }
+                Err(response) => return response,
+            },
+        };
+        let valid = match (&cookie, &submitted) {
+            (Some(cookie), Some(submitted)) => cookie.as_bytes().ct_eq(submitted).into(),
+            _ => false,
+        };
+        if !valid {
+            return (StatusCode::FORBIDDEN, "Invalid csrf token").into_response();
+        }
+    }
+
+    let (token, is_new) = match cookie {
+        Some(token) => (CsrfToken(token), false),
+        None => (csrf.token(), true),
+    };
+    request.extensions_mut().insert(token.clone());
+
+    let mut response = next.run(request).await;
+    if is_new {
+        let secure = match csrf.secure {
+            true => "; Secure",
+            false => "",
+        };
+        let cookie = format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax{}",
+            csrf.cookie_name(),
+            token,
+            secure
+        );
+        if let Ok(cookie) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, cookie);
+        }
+    }
+
+    response
+}
+
+fn cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (pair_name, value) = pair.trim().split_once('=')?;
+            (pair_name == name).then(|| value.to_owned())
+        })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
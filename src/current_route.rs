@@ -0,0 +1,247 @@
+//! Which route a request is for, to mark active links in navigation menus.
+//!
+//! ```rust,ignore
+//! let class = match Route::Todos.is_current(&uri) || Route::Todos.is_ancestor_of(&uri) {
+//!     true => "active",
+//!     false => "",
+//! };
+//! ```
+
+use crate::{Href, MatchedRoute, RouteInfo};
+use axum_core::extract::FromRequestParts;
+use http::{request::Parts, StatusCode, Uri};
+use std::{ops::Deref, str::FromStr};
+
+/// The variant the request was routed to, parsed back from the path and query.
+///
+/// ```rust,ignore
+/// async fn next_page(CurrentRoute(route): CurrentRoute<Route>) -> Html<String> {
+///     Html(format!(r#"<a href="{}">Next</a>"#, route.url().with_param("page", 2)))
+/// }
+/// ```
+///
+/// Only for enums declared with `#[router(current_route)]` or
+/// `#[resource(current_route)]`. Path and named fields are parsed with
+/// `FromStr`, `#[query]` structs with `#[derive(FromQueryString)]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CurrentRoute<R>(pub R);
+
+/// Parses a variant back from the route, path and query of a request.
+///
+/// Implemented by `#[router(current_route)]` and `#[resource(current_route)]`,
+/// which need every field to implement `FromStr`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be parsed from a request",
+    note = "add `current_route` to the `#[router]` or `#[resource]` of `{Self}`"
+)]
+pub trait FromRoute: Sized {
+    fn from_route(route: &RouteInfo, path: &str, query: Option<&str>) -> Option<Self>;
+}
+
+/// Parses a `#[query]` struct from a query string written by `QueryString`.
+///
+/// Implemented by `#[derive(FromQueryString)]`, which needs every field to
+/// implement `FromStr`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be parsed from a query string",
+    note = "add `#[derive(FromQueryString)]` to `{Self}`"
+)]
+pub trait FromQueryString {
+    fn from_query_string(query: &str) -> Self;
+}
+
+impl<R> Deref for CurrentRoute<R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<R, S> FromRequestParts<S> for CurrentRoute<R>
+where
+    R: FromRoute,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let route = MatchedRoute::from_request_parts(parts, state).await?;
+        R::from_route(&route, parts.uri.path(), parts.uri.query())
+            .map(CurrentRoute)
+            .ok_or((
+                StatusCode::BAD_REQUEST,
+                "The request doesn't match the route",
+            ))
+    }
+}
+
+/// Whether `uri` is the page `href` links to, ignoring the query, the
+/// fragment, a trailing slash and the base path.
+#[doc(hidden)]
+pub fn is_current(href: &Href, uri: &Uri) -> bool {
+    let expected = segments(href.path());
+    paths(href.base_path(), uri).any(|path| segments(path) == expected)
+}
+
+/// Whether `uri` is below the page `href` links to, e.g. `/todos` is an
+/// ancestor of `/todos/1/edit` but not of `/todos` or `/todosx`.
+#[doc(hidden)]
+pub fn is_ancestor_of(href: &Href, uri: &Uri) -> bool {
+    let expected = segments(href.path());
+    paths(href.base_path(), uri).any(|path| {
+        let path = segments(path);
+        path.len() > expected.len() && path.starts_with(&expected)
+    })
+}
+
+/// The request path, and the request path without the base path when it has one.
+fn paths<'a>(base_path: Option<&str>, uri: &'a Uri) -> impl Iterator<Item = &'a str> {
+    let path = uri.path();
+    let stripped = base_path.and_then(|base_path| {
+        path.strip_prefix(base_path)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+    });
+
+    std::iter::once(path).chain(stripped)
+}
+
+fn segments(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| urldecode(segment, false))
+        .collect()
+}
+
+/// The decoded values of the `{param}` and `{*rest}` segments of `pattern` in
+/// `path`, or `None` when the literal segments don't match.
+#[doc(hidden)]
+pub fn params(pattern: &str, path: &str) -> Option<Vec<String>> {
+    let mut params = Vec::new();
+    let mut path = path.trim_start_matches('/').split('/');
+    for part in pattern.trim_start_matches('/').split('/') {
+        if part.starts_with("{*") {
+            params.push(urldecode(
+                &path.by_ref().collect::<Vec<_>>().join("/"),
+                false,
+            ));
+            continue;
+        }
+        let segment = path.next()?;
+        match part.contains('{') {
+            true => params.push(urldecode(segment, false)),
+            false if urldecode(segment, false) == part => {}
+            false => return None,
+        }
+    }
+
+    path.all(str::is_empty).then_some(params)
+}
+
+#[doc(hidden)]
+pub fn param<T: FromStr>(params: &[String], index: usize) -> Option<T> {
+    params.get(index)?.parse().ok()
+}
+
+/// The decoded value of `name` in a query string, `None` when it is missing
+/// or doesn't parse.
+#[doc(hidden)]
+pub fn query_value<T: FromStr>(query: Option<&str>, name: &str) -> Option<T> {
+    raw_query_value(query, name)?.parse().ok()
+}
+
+/// The value of a named field, which `url()` writes with `{:?}`.
+#[doc(hidden)]
+pub fn debug_query_value<T: FromStr>(query: Option<&str>, name: &str) -> Option<T> {
+    undebug(&raw_query_value(query, name)?)?.parse().ok()
+}
+
+/// The value of a named `Option` field, written as `Some(..)` or `None`.
+#[doc(hidden)]
+pub fn debug_query_option<T: FromStr>(query: Option<&str>, name: &str) -> Option<Option<T>> {
+    let Some(value) = raw_query_value(query, name) else {
+        return Some(None);
+    };
+    match value
+        .strip_prefix("Some(")
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        Some(inner) => Some(Some(undebug(inner)?.parse().ok()?)),
+        None if value == "None" => Some(None),
+        None => None,
+    }
+}
+
+fn raw_query_value(query: Option<&str>, name: &str) -> Option<String> {
+    query?
+        .split('&')
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .find(|(key, _)| urldecode(key, true) == name)
+        .map(|(_, value)| urldecode(value, true))
+}
+
+/// Reverses the `Debug` quoting and escaping of strings and chars, other
+/// values are unchanged.
+fn undebug(value: &str) -> Option<String> {
+    let quoted = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')));
+    let Some(quoted) = quoted else {
+        return Some(value.to_owned());
+    };
+
+    let mut unescaped = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => unescaped.push('\n'),
+            'r' => unescaped.push('\r'),
+            't' => unescaped.push('\t'),
+            '0' => unescaped.push('\0'),
+            'u' => {
+                let hex = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|c| *c != '}')
+                    .collect::<String>();
+                unescaped.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            c => unescaped.push(c),
+        }
+    }
+
+    Some(unescaped)
+}
+
+/// Percent decoding, with `+` as a space in query strings.
+fn urldecode(s: &str, plus_as_space: bool) -> String {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        match b {
+            b'+' if plus_as_space => bytes.push(b' '),
+            b'%' => {
+                let hex = rest
+                    .get(..2)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match hex {
+                    Some(decoded) => {
+                        bytes.push(decoded);
+                        rest = &rest[2..];
+                    }
+                    None => bytes.push(b),
+                }
+            }
+            b => bytes.push(b),
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
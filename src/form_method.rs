@@ -0,0 +1,182 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    response::{IntoResponse, Response},
+    Router,
+};
+use http::{header, HeaderName, Method, StatusCode};
+use std::{
+    convert::Infallible,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+const OVERRIDE_HEADER: HeaderName = HeaderName::from_static("x-http-method-override");
+
+/// The form body limit of `method_override`, axum's default body limit.
+pub(crate) const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Routes `POST` requests with a `_method` form field or an
+/// `X-HTTP-Method-Override` header of `PUT`, `PATCH` or `DELETE` as that method,
+/// so resources work from plain html forms.
+///
+/// The method has to change before routing, so this wraps the whole router:
+///
+/// ```rust,ignore
+/// let app = enum_router::method_override(Route::router());
+/// ```
+///
+/// Form bodies up to 2 MiB, axum's default body limit, are searched for the
+/// `_method` field, see `method_override_with_limit`.
+pub fn method_override(router: Router) -> Router {
+    method_override_with_limit(router, DEFAULT_BODY_LIMIT)
+}
+
+/// `method_override` searching form bodies up to `limit` bytes, e.g. the
+/// limit of the router's `DefaultBodyLimit`.
+///
+/// Larger bodies with a `Content-Length` are passed on without an override,
+/// so the router's own body limit answers them. Larger bodies without one
+/// are already read when the limit is hit, and get a `413`.
+pub fn method_override_with_limit(router: Router, limit: usize) -> Router {
+    Router::new().fallback_service(MethodOverride { router, limit })
+}
+
+#[derive(Clone)]
+struct MethodOverride {
+    router: Router,
+    limit: usize,
+}
+
+impl Service<Request> for MethodOverride {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let mut router = self.router.clone();
+        let limit = self.limit;
+        Box::pin(async move {
+            let request = match override_method(request, limit).await {
+                Ok(request) => request,
+                Err(response) => return Ok(response),
+            };
+
+            router.call(request).await
+        })
+    }
+}
+
+async fn override_method(request: Request, limit: usize) -> Result<Request, Response> {
+    if request.method() != Method::POST {
+        return Ok(request);
+    }
+
+    if let Some(method) = request
+        .headers()
+        .get(OVERRIDE_HEADER)
+        .and_then(|value| overridable(value.as_bytes()))
+    {
+        return Ok(with_method(request, method));
+    }
+
+    let (request, method) = form_field(request, "_method", limit).await?;
+
+    Ok(match method.as_deref().and_then(overridable) {
+        Some(method) => with_method(request, method),
+        None => request,
+    })
+}
+
+/// The raw value of the `name` field of a urlencoded form body, buffering
+/// the body and putting it back into the request.
+///
+/// Requests that aren't forms, and forms that are empty or declare a
+/// `Content-Length` over `limit`, are returned untouched without the field.
+pub(crate) async fn form_field(
+    request: Request,
+    name: &str,
+    limit: usize,
+) -> Result<(Request, Option<Vec<u8>>), Response> {
+    let headers = request.headers();
+    let is_form = headers.get(header::CONTENT_TYPE).is_some_and(|value| {
+        value
+            .as_bytes()
+            .starts_with(b"application/x-www-form-urlencoded")
+    });
+    let length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<usize>().ok());
+    if !is_form || length.is_some_and(|length| length == 0 || length > limit) {
+        return Ok((request, None));
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, limit)
+        .await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE.into_response())?;
+    let value = bytes.split(|b| *b == b'&').find_map(|pair| {
+        pair.strip_prefix(name.as_bytes())?
+            .strip_prefix(b"=")
+            .map(<[u8]>::to_vec)
+    });
+
+    Ok((Request::from_parts(parts, Body::from(bytes)), value))
+}
+
+fn overridable(method: &[u8]) -> Option<Method> {
+    match method.to_ascii_uppercase().as_slice() {
+        b"PUT" => Some(Method::PUT),
+        b"PATCH" => Some(Method::PATCH),
+        b"DELETE" => Some(Method::DELETE),
+        _ => None,
+    }
+}
+
+fn with_method(mut request: Request, method: Method) -> Request {
+    *request.method_mut() = method;
+    request
+}
+
+/// The `method` attribute and hidden `_method` input of an html form for a
+/// route, e.g. `method="post"` and `<input type="hidden" name="_method" value="DELETE">`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormMethodFields {
+    method: Method,
+}
+
+impl FormMethodFields {
+    pub fn new(method: Method) -> Self {
+        Self { method }
+    }
+
+    /// `"get"` for `GET`, `"post"` for every other method.
+    pub fn method(&self) -> &'static str {
+        match self.method {
+            Method::GET => "get",
+            _ => "post",
+        }
+    }
+
+    /// The hidden `_method` input, empty for `GET` and `POST`.
+    pub fn hidden_input(&self) -> String {
+        match self.method {
+            Method::GET | Method::POST => String::new(),
+            ref method => format!(r#"<input type="hidden" name="_method" value="{}">"#, method),
+        }
+    }
+}
+
+/// The `method` attribute, e.g. `method="post"`.
+impl fmt::Display for FormMethodFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, r#"method="{}""#, self.method())
+    }
+}
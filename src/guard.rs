@@ -0,0 +1,98 @@
+use axum::{
+    extract::Request,
+    handler::Handler,
+    response::{IntoResponse, Response},
+};
+use axum_core::extract::FromRequestParts;
+use http::request::Parts;
+use std::{future::Future, pin::Pin};
+
+/// An async fn taking extractors and returning `Result<(), impl IntoResponse>`,
+/// run in front of a route's handler with `#[get("/billing", guard = is_owner)]`.
+///
+/// When the guard returns an error, the error is the response and the
+/// handler doesn't run.
+pub trait Guard<T, S>: Clone + Send + Sync + 'static {
+    fn check(
+        self,
+        parts: &mut Parts,
+        state: &S,
+    ) -> impl Future<Output = Result<(), Response>> + Send;
+}
+
+macro_rules! impl_guard {
+    ($($ty:ident),*) => {
+        #[allow(non_snake_case, unused_variables)]
+        impl<F, Fut, E, S, $($ty,)*> Guard<($($ty,)*), S> for F
+        where
+            F: FnOnce($($ty,)*) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future<Output = Result<(), E>> + Send,
+            E: IntoResponse,
+            S: Send + Sync,
+            $($ty: FromRequestParts<S> + Send,)*
+        {
+            async fn check(self, parts: &mut Parts, state: &S) -> Result<(), Response> {
+                $(
+                    let $ty = $ty::from_request_parts(parts, state)
+                        .await
+                        .map_err(IntoResponse::into_response)?;
+                )*
+                self($($ty,)*).await.map_err(IntoResponse::into_response)
+            }
+        }
+    };
+}
+
+impl_guard!();
+impl_guard!(T1);
+impl_guard!(T1, T2);
+impl_guard!(T1, T2, T3);
+impl_guard!(T1, T2, T3, T4);
+impl_guard!(T1, T2, T3, T4, T5);
+impl_guard!(T1, T2, T3, T4, T5, T6);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+impl_guard!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
+
+/// A handler that runs `guard` first, used by the generated `router()`.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct Guarded<G, H> {
+    guard: G,
+    handler: H,
+}
+
+impl<G, H> Guarded<G, H> {
+    pub fn new(guard: G, handler: H) -> Self {
+        Self { guard, handler }
+    }
+}
+
+impl<G, H, S, GT, T> Handler<(GT, T), S> for Guarded<G, H>
+where
+    G: Guard<GT, S>,
+    H: Handler<T, S>,
+    S: Clone + Send + Sync + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = Response> + Send>>;
+
+    fn call(self, request: Request, state: S) -> Self::Future {
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            if let Err(response) = self.guard.check(&mut parts, &state).await {
+                return response;
+            }
+
+            self.handler
+                .call(Request::from_parts(parts, body), state)
+                .await
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use crate::urlencode;
+use http::{
+    header::{HeaderValue, InvalidHeaderValue},
+    uri::InvalidUri,
+    Method, Uri,
+};
+use std::fmt;
+
+/// A url generated from a route variant.
+///
+/// Keeps the path, query and fragment apart so parameters can be appended
+/// safely, and remembers the http method the variant is routed with.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Href {
+    method: Method,
+    base: Option<String>,
+    base_path: Option<String>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl Href {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        let url = url.into();
+        let (url, fragment) = match url.split_once('#') {
+            Some((url, fragment)) => (url.to_owned(), non_empty(fragment)),
+            None => (url, None),
+        };
+        let (path, query) = match url.split_once('?') {
+            Some((path, query)) => (path.to_owned(), non_empty(query)),
+            None => (url, None),
+        };
+
+        Self {
+            method,
+            base: None,
+            base_path: None,
+            path,
+            query,
+            fragment,
+        }
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// The path as matched by `Route::router()`, without the base path.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The mount prefix, see [`crate::base_path`].
+    pub fn base_path(&self) -> Option<&str> {
+        self.base_path.as_deref()
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// The path and query without the base path and fragment, the uri
+    /// `Route::router()` itself matches.
+    pub(crate) fn router_uri(&self) -> String {
+        match &self.query {
+            Some(query) => format!("{}?{}", self.path, query),
+            None => self.path.clone(),
+        }
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /// The scheme and authority set with [`Href::absolute`], if any.
+    pub fn base(&self) -> Option<&str> {
+        self.base.as_deref()
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.base.is_some()
+    }
+
+    /// Appends an already encoded query string, e.g. `"page=2&sort=name"`.
+    pub fn with_query(mut self, query: impl fmt::Display) -> Self {
+        let query = query.to_string();
+        let query = query.trim_start_matches('?');
+        if query.is_empty() {
+            return self;
+        }
+        self.query = Some(match self.query {
+            Some(existing) => format!("{}&{}", existing, query),
+            None => query.to_owned(),
+        });
+
+        self
+    }
+
+    /// Appends a single `key=value` pair, urlencoding both sides.
+    pub fn with_param(self, key: impl fmt::Display, value: impl fmt::Display) -> Self {
+        let pair = format!("{}={}", urlencode(key), urlencode(value));
+        self.with_query(pair)
+    }
+
+    pub fn with_fragment(mut self, fragment: impl fmt::Display) -> Self {
+        self.fragment = non_empty(fragment.to_string().trim_start_matches('#'));
+        self
+    }
+
+    /// Prefixes the path with the sub-path the app is mounted under, e.g. `"/app"`.
+    ///
+    /// `"app/"` is normalized to `"/app"`, a base path that isn't a plain path
+    /// is ignored, see [`crate::base_path::forwarded_prefix`].
+    pub fn with_base_path(mut self, base_path: impl fmt::Display) -> Self {
+        self.base_path = crate::base_path::normalize(&base_path.to_string());
+        self
+    }
+
+    /// Prefixes the url with a scheme and authority, e.g. `"https://example.com"`.
+    pub fn absolute(mut self, base: impl fmt::Display) -> Self {
+        self.base = non_empty(base.to_string().trim_end_matches('/'));
+        self
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    match s.is_empty() {
+        true => None,
+        false => Some(s.to_owned()),
+    }
+}
+
+impl fmt::Display for Href {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(base) = &self.base {
+            f.write_str(base)?;
+        }
+        if let Some(base_path) = &self.base_path {
+            f.write_str(base_path)?;
+        }
+        f.write_str(&self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Href> for String {
+    fn from(value: Href) -> Self {
+        value.to_string()
+    }
+}
+
+impl PartialEq<str> for Href {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Href {
+    fn eq(&self, other: &&str) -> bool {
+        self.to_string().as_str() == *other
+    }
+}
+
+impl TryFrom<&Href> for Uri {
+    type Error = InvalidUri;
+
+    fn try_from(value: &Href) -> Result<Self, Self::Error> {
+        value.to_string().parse()
+    }
+}
+
+impl TryFrom<Href> for Uri {
+    type Error = InvalidUri;
+
+    fn try_from(value: Href) -> Result<Self, Self::Error> {
+        Uri::try_from(&value)
+    }
+}
+
+impl TryFrom<&Href> for HeaderValue {
+    type Error = InvalidHeaderValue;
+
+    fn try_from(value: &Href) -> Result<Self, Self::Error> {
+        HeaderValue::try_from(value.to_string())
+    }
+}
+
+impl TryFrom<Href> for HeaderValue {
+    type Error = InvalidHeaderValue;
+
+    fn try_from(value: Href) -> Result<Self, Self::Error> {
+        HeaderValue::try_from(&value)
+    }
+}
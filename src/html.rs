@@ -0,0 +1,51 @@
+//! Html tags built from a route's `url()` and `method()`, so links and forms
+//! can't drift from the routes.
+
+use crate::Routable;
+
+/// Escapes `&`, `<`, `>`, `"` and `'` for html text and quoted attributes.
+pub fn escape(s: impl AsRef<str>) -> String {
+    let s = s.as_ref();
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// `<a href="...">text</a>`, with the url and `text` escaped.
+pub fn link_to(route: &impl Routable, text: impl AsRef<str>) -> String {
+    format!(
+        r#"<a href="{}">{}</a>"#,
+        escape(route.url().to_string()),
+        escape(text)
+    )
+}
+
+/// `<form action="..." method="...">`, followed by the hidden `_method` input
+/// for `method_override` and a hidden `csrf_token` input when a token is given.
+pub fn form_open(route: &impl Routable, csrf_token: Option<&str>) -> String {
+    let fields = crate::FormMethodFields::new(route.method());
+    let mut form = format!(
+        r#"<form action="{}" {}>{}"#,
+        escape(route.url().to_string()),
+        fields,
+        fields.hidden_input()
+    );
+    if let Some(token) = csrf_token {
+        form.push_str(&format!(
+            r#"<input type="hidden" name="csrf_token" value="{}">"#,
+            escape(token)
+        ));
+    }
+
+    form
+}
@@ -1,6 +1,52 @@
-pub use enum_router_macro::{resource, router, QueryString, Routes};
+pub use enum_router_macro::{resource, router, FromQueryString, QueryString, Routes};
 extern crate self as enum_router;
 
+pub mod base_path;
+mod check;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "coverage")]
+pub mod coverage;
+#[cfg(feature = "csrf")]
+pub mod csrf;
+pub mod current_route;
+mod form_method;
+mod guard;
+mod href;
+pub mod html;
+mod matched_route;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+mod options;
+mod redirect;
+mod routable;
+mod route_info;
+pub mod self_test;
+pub mod sitemap;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "typescript")]
+pub mod typescript;
+mod url_for;
+
+#[doc(hidden)]
+pub use check::LastArg;
+pub use current_route::{CurrentRoute, FromQueryString, FromRoute};
+pub use form_method::{method_override, method_override_with_limit, FormMethodFields};
+pub use guard::Guard;
+#[doc(hidden)]
+pub use guard::Guarded;
+pub use href::Href;
+#[doc(hidden)]
+pub use matched_route::insert_matched_route;
+pub use matched_route::MatchedRoute;
+#[doc(hidden)]
+pub use options::register_routes;
+pub use options::Cors;
+pub use routable::Routable;
+pub use route_info::{routes_table, FieldInfo, RouteInfo};
+pub use url_for::{BaseUrl, InvalidBaseUrl, UrlFor};
+
 pub fn urlencode(s: impl std::fmt::Display) -> String {
     s.to_string()
         .chars()
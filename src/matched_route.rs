@@ -0,0 +1,86 @@
+use crate::RouteInfo;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use axum_core::extract::FromRequestParts;
+use http::{request::Parts, StatusCode};
+use std::ops::Deref;
+
+/// The route a request was routed to.
+///
+/// `Route::router()` inserts it into the request extensions before the
+/// handler runs, and into the response extensions. Middleware added with
+/// `Router::layer` wraps the routes, so it finds the route on the response.
+///
+/// ```rust,ignore
+/// async fn log(request: Request, next: Next) -> Response {
+///     let response = next.run(request).await;
+///     if let Some(route) = response.extensions().get::<MatchedRoute>() {
+///         println!("{}::{} {}", route.enum_name, route.variant, response.status());
+///     }
+///     response
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchedRoute(pub &'static RouteInfo);
+
+impl Deref for MatchedRoute {
+    type Target = RouteInfo;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<S> FromRequestParts<S> for MatchedRoute
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<MatchedRoute>().copied().ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "No MatchedRoute, the route isn't registered by a route enum",
+        ))
+    }
+}
+
+/// Middleware added to every route by `Route::router()`.
+///
+/// With the `tracing` feature the rest of the request runs in a `route` span
+/// with the route's `name`, `method` and `pattern` as fields.
+///
+/// Nested enums add it again inside the one of the enum they're nested in,
+/// which already inserted the route with its prefixed name, so the outermost
+/// enum's route is kept.
+#[doc(hidden)]
+pub async fn insert_matched_route(
+    State(route): State<MatchedRoute>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if request.extensions().get::<MatchedRoute>().is_some() {
+        return next.run(request).await;
+    }
+    request.extensions_mut().insert(route);
+    #[cfg(feature = "tracing")]
+    let mut response = {
+        use tracing::Instrument;
+
+        let span = tracing::info_span!(
+            "route",
+            name = route.name,
+            method = route.method,
+            pattern = route.pattern
+        );
+        next.run(request).instrument(span).await
+    };
+    #[cfg(not(feature = "tracing"))]
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(route);
+
+    response
+}
@@ -0,0 +1,134 @@
+//! OpenAPI 3.1 documents built from the `ROUTES` of a route enum.
+
+use crate::{FieldInfo, RouteInfo};
+pub use serde_json::Value;
+use serde_json::{json, Map};
+
+/// The operations documented for an `#[any]` route.
+const ANY: &[&str] = &[
+    "GET", "PUT", "POST", "DELETE", "OPTIONS", "HEAD", "PATCH", "TRACE",
+];
+
+pub fn document(routes: &[RouteInfo], title: &str, version: &str) -> Value {
+    let mut paths = Map::new();
+    for route in routes {
+        let path = paths
+            .entry(route.pattern.replace("{*", "{"))
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(path) = path {
+            for method in route.methods {
+                let methods = match *method {
+                    "*" => ANY,
+                    _ => std::slice::from_ref(method),
+                };
+                for method in methods {
+                    path.insert(method.to_lowercase(), operation(route, method));
+                }
+            }
+        }
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": title,
+            "version": version,
+        },
+        "paths": paths,
+    })
+}
+
+/// The operation of `route` for `method`, with the route's `name` as its
+/// operationId. Methods other than the route's own `method` get it as a
+/// suffix, e.g. `feed_head`, so every operationId is unique.
+fn operation(route: &RouteInfo, method: &str) -> Value {
+    let mut operation = Map::new();
+    let mut operation_id = route.name.to_owned();
+    if method != route.method {
+        operation_id = format!("{}_{}", operation_id, method.to_lowercase());
+    }
+    operation.insert("operationId".to_owned(), json!(operation_id));
+    operation.insert("tags".to_owned(), json!([route.enum_name]));
+
+    let mut lines = route.doc.lines();
+    if let Some(summary) = lines.next().filter(|line| !line.is_empty()) {
+        operation.insert("summary".to_owned(), json!(summary));
+    }
+    let description = lines.collect::<Vec<_>>().join("\n");
+    if !description.trim().is_empty() {
+        operation.insert("description".to_owned(), json!(description.trim()));
+    }
+
+    let parameters = parameters(route);
+    if !parameters.is_empty() {
+        operation.insert("parameters".to_owned(), Value::Array(parameters));
+    }
+    if let Some(body) = route.body {
+        operation.insert(
+            "requestBody".to_owned(),
+            json!({
+                "required": true,
+                "content": { "application/json": { "schema": { "title": body } } },
+            }),
+        );
+    }
+    let response = match route.response {
+        Some(response) => json!({
+            "description": "OK",
+            "content": { "application/json": { "schema": { "title": response } } },
+        }),
+        None => json!({ "description": "OK" }),
+    };
+    operation.insert("responses".to_owned(), json!({ "200": response }));
+
+    Value::Object(operation)
+}
+
+fn parameters(route: &RouteInfo) -> Vec<Value> {
+    let mut parameters = Vec::new();
+    for field in route.fields {
+        if field.query {
+            parameters.extend(field.fields.iter().map(|field| parameter(field, "query")));
+        } else if route.has_path_param(field.name) {
+            parameters.push(parameter(field, "path"));
+        } else {
+            parameters.push(parameter(field, "query"));
+        }
+    }
+
+    parameters
+}
+
+fn parameter(field: &FieldInfo, location: &str) -> Value {
+    let (ty, optional) = match field
+        .ty
+        .strip_prefix("Option<")
+        .and_then(|ty| ty.strip_suffix('>'))
+    {
+        Some(ty) => (ty, true),
+        None => (field.ty, false),
+    };
+
+    json!({
+        "name": field.name,
+        "in": location,
+        "required": location == "path" || !optional,
+        "schema": schema(ty),
+    })
+}
+
+/// Json schema for a rust type name, e.g. `"i64"`.
+pub fn schema(ty: &str) -> Value {
+    match ty {
+        "i8" | "i16" | "i32" | "u8" | "u16" | "u32" => {
+            json!({ "type": "integer", "format": "int32" })
+        }
+        "i64" | "i128" | "isize" | "u64" | "u128" | "usize" => {
+            json!({ "type": "integer", "format": "int64" })
+        }
+        "f32" => json!({ "type": "number", "format": "float" }),
+        "f64" => json!({ "type": "number", "format": "double" }),
+        "bool" => json!({ "type": "boolean" }),
+        _ => json!({ "type": "string" }),
+    }
+}
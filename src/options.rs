@@ -0,0 +1,111 @@
+use crate::RouteInfo;
+use axum::{
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+    Router,
+};
+use http::{header, HeaderValue, Method, StatusCode};
+
+/// CORS preflight headers for the automatic `OPTIONS` responses, declared on
+/// the enum with `#[cors(origin = "https://example.com", headers = "content-type", max_age = 600)]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cors {
+    /// `Access-Control-Allow-Origin`.
+    pub origin: &'static str,
+    /// `Access-Control-Allow-Headers`, omitted when empty.
+    pub headers: &'static str,
+    /// `Access-Control-Max-Age` in seconds.
+    pub max_age: Option<u64>,
+}
+
+/// `Route::router()`: registers the method router of every route in `routes`,
+/// the flattened `ROUTES` of the enum, at its pattern.
+///
+/// Every path then gets one fallback allowing the methods of all its routes,
+/// including those of nested routers, and none if an `any` route takes every
+/// method. The `cors` of the first route of a path is used.
+#[doc(hidden)]
+pub fn register_routes<S>(
+    routes: &'static [RouteInfo],
+    method_routers: Vec<(MethodRouter<S>, Option<Cors>)>,
+) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let mut router = Router::new();
+    let mut paths: Vec<(&'static str, Vec<&'static str>, Option<Cors>)> = Vec::new();
+    for (route, (method_router, cors)) in routes.iter().zip(method_routers) {
+        router = router.route(route.pattern, method_router);
+        match paths
+            .iter_mut()
+            .find(|(pattern, _, _)| *pattern == route.pattern)
+        {
+            Some((_, methods, _)) => methods.extend(route.methods),
+            None => paths.push((route.pattern, route.methods.to_vec(), cors)),
+        }
+    }
+
+    for (pattern, methods, cors) in paths {
+        if methods.contains(&"*") {
+            continue;
+        }
+        let allow = allow(&methods);
+        router = router.route(
+            pattern,
+            MethodRouter::new()
+                .fallback(move |method: Method| method_not_allowed(method, allow.clone(), cors)),
+        );
+    }
+
+    router
+}
+
+/// The `Allow` header of a path with `methods`, with `HEAD` after `GET` and
+/// `OPTIONS` last.
+fn allow(methods: &[&str]) -> HeaderValue {
+    let mut allow = Vec::new();
+    for method in methods {
+        if !allow.contains(method) {
+            allow.push(*method);
+        }
+        if *method == "GET" && !allow.contains(&"HEAD") {
+            allow.push("HEAD");
+        }
+    }
+    if !allow.contains(&"OPTIONS") {
+        allow.push("OPTIONS");
+    }
+
+    HeaderValue::from_str(&allow.join(", ")).expect("method names are valid header values")
+}
+
+/// The fallback of every path in `Route::router()`: `204` with an `Allow`
+/// header for `OPTIONS`, `405` with an `Allow` header for other methods.
+async fn method_not_allowed(method: Method, allow: HeaderValue, cors: Option<Cors>) -> Response {
+    let status = match method {
+        Method::OPTIONS => StatusCode::NO_CONTENT,
+        _ => StatusCode::METHOD_NOT_ALLOWED,
+    };
+    let mut response = status.into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::ALLOW, allow.clone());
+
+    if let (Method::OPTIONS, Some(cors)) = (method, cors) {
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            HeaderValue::from_static(cors.origin),
+        );
+        headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, allow);
+        if !cors.headers.is_empty() {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static(cors.headers),
+            );
+        }
+        if let Some(max_age) = cors.max_age {
+            headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age));
+        }
+    }
+
+    response
+}
@@ -0,0 +1,43 @@
+use crate::Href;
+use axum_core::response::{IntoResponse, Response};
+use http::{header, HeaderName, HeaderValue, StatusCode};
+
+impl Href {
+    /// `303 See Other`, the usual response after a form submission.
+    pub fn redirect(&self) -> Response {
+        self.respond(StatusCode::SEE_OTHER, header::LOCATION)
+    }
+
+    /// `308 Permanent Redirect`, keeps the method and body.
+    pub fn redirect_permanent(&self) -> Response {
+        self.respond(StatusCode::PERMANENT_REDIRECT, header::LOCATION)
+    }
+
+    /// `307 Temporary Redirect`, keeps the method and body.
+    pub fn redirect_temporary(&self) -> Response {
+        self.respond(StatusCode::TEMPORARY_REDIRECT, header::LOCATION)
+    }
+
+    /// Full page redirect for htmx requests through the `HX-Redirect` header.
+    pub fn htmx_redirect(&self) -> Response {
+        self.respond(StatusCode::OK, HeaderName::from_static("hx-redirect"))
+    }
+
+    /// Client side navigation for htmx requests through the `HX-Location` header.
+    pub fn htmx_location(&self) -> Response {
+        self.respond(StatusCode::OK, HeaderName::from_static("hx-location"))
+    }
+
+    fn respond(&self, status: StatusCode, name: HeaderName) -> Response {
+        match HeaderValue::try_from(self) {
+            Ok(value) => (status, [(name, value)]).into_response(),
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+}
+
+impl IntoResponse for Href {
+    fn into_response(self) -> Response {
+        self.redirect()
+    }
+}
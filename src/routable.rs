@@ -0,0 +1,31 @@
+use crate::{sitemap, Href, RouteInfo};
+use http::Method;
+
+/// Implemented for every enum generated by `#[router]` and `#[resource]`,
+/// so runtime helpers can work with any route variant.
+pub trait Routable {
+    const ROUTES: &'static [RouteInfo];
+
+    fn url(&self) -> Href;
+
+    fn method(&self) -> Method;
+
+    fn pattern(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    fn sitemap_options(&self) -> Option<sitemap::Options>;
+
+    /// The `TypeId` and name of the variant's `body = Type`, checked by
+    /// `Client::call_with`. Only generated with the `client` feature.
+    #[doc(hidden)]
+    fn __body_type(&self) -> Option<(std::any::TypeId, &'static str)> {
+        None
+    }
+
+    /// Every unit variant, including those of nested enums, for `Sitemap`.
+    #[doc(hidden)]
+    fn __sitemap_units() -> Vec<Self>
+    where
+        Self: Sized;
+}
@@ -0,0 +1,143 @@
+use http::Method;
+
+/// Static description of a route registered by `Route::router()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// Http method of the variant's `url()`, e.g. `"GET"`.
+    pub method: &'static str,
+    /// Every method the route accepts, e.g. `["GET", "HEAD"]`, or `["*"]` for `#[any]`.
+    pub methods: &'static [&'static str],
+    /// The axum path pattern, e.g. `"/todos/{id}/edit"`.
+    pub pattern: &'static str,
+    /// Name of the enum declaring the variant, e.g. `"Sessions"`.
+    pub enum_name: &'static str,
+    /// Name of the variant, e.g. `"Edit"`.
+    pub variant: &'static str,
+    /// Path of the handler function, e.g. `"edit_todo"` or `"Sessions::edit"`.
+    pub handler: &'static str,
+    /// Low cardinality name for metrics and logs, e.g. `"edit_todo"` or
+    /// `"sessions.edit"`.
+    pub name: &'static str,
+    /// The guards declared with `guard = fn`, e.g. `["is_owner"]`.
+    pub guards: &'static [&'static str],
+    /// Whether `csrf::protect` checks the route's non-GET requests, false for `#[csrf(skip)]`.
+    pub csrf: bool,
+    pub fields: &'static [FieldInfo],
+    /// The variant's doc comment.
+    pub doc: &'static str,
+    /// The request body type declared with `body = Type`.
+    pub body: Option<&'static str>,
+    /// The response type declared with `response = Type`.
+    pub response: Option<&'static str>,
+}
+
+/// A field of a route variant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// The path parameter the field fills, or its name or position.
+    pub name: &'static str,
+    pub ty: &'static str,
+    /// Whether the field is a `#[query]` struct.
+    pub query: bool,
+    /// The fields of a `#[query]` struct.
+    pub fields: &'static [FieldInfo],
+}
+
+impl RouteInfo {
+    pub const EMPTY: Self = Self {
+        method: "",
+        methods: &[],
+        pattern: "",
+        enum_name: "",
+        variant: "",
+        handler: "",
+        name: "",
+        guards: &[],
+        csrf: true,
+        fields: &[],
+        doc: "",
+        body: None,
+        response: None,
+    };
+
+    pub fn method(&self) -> Method {
+        Method::from_bytes(self.method.as_bytes()).unwrap_or_default()
+    }
+
+    /// Whether the pattern has a `{name}` or `{*name}` segment.
+    #[cfg(any(feature = "openapi", feature = "typescript"))]
+    pub(crate) fn has_path_param(&self, name: &str) -> bool {
+        self.pattern
+            .split('/')
+            .filter_map(|part| part.strip_prefix('{')?.strip_suffix('}'))
+            .any(|param| param.trim_start_matches('*') == name)
+    }
+
+    #[doc(hidden)]
+    pub const fn count(sources: &[&[RouteInfo]]) -> usize {
+        let mut count = 0;
+        let mut i = 0;
+        while i < sources.len() {
+            count += sources[i].len();
+            i += 1;
+        }
+
+        count
+    }
+
+    /// Flattens the routes of an enum and its nested routers at compile time.
+    #[doc(hidden)]
+    pub const fn flatten<const N: usize>(sources: &[&[RouteInfo]]) -> [RouteInfo; N] {
+        let mut routes = [Self::EMPTY; N];
+        let mut n = 0;
+        let mut i = 0;
+        while i < sources.len() {
+            let mut j = 0;
+            while j < sources[i].len() {
+                routes[n] = sources[i][j];
+                n += 1;
+                j += 1;
+            }
+            i += 1;
+        }
+
+        routes
+    }
+}
+
+/// Renders an aligned table of method, pattern, variant and handler.
+#[doc(hidden)]
+pub fn routes_table(routes: &[RouteInfo], variants: &[String]) -> String {
+    let header = ["Method", "Pattern", "Route", "Handler"].map(str::to_owned);
+    let rows = std::iter::once(header)
+        .chain(routes.iter().zip(variants).map(|(route, variant)| {
+            [
+                route.methods.join(","),
+                route.pattern.to_owned(),
+                variant.clone(),
+                route.handler.to_owned(),
+            ]
+        }))
+        .collect::<Vec<_>>();
+
+    let widths = (0..4)
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
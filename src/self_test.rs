@@ -0,0 +1,168 @@
+//! Checks that every route variant's `url()` and `method()` are matched by
+//! the patterns `Route::router()` registers.
+
+use crate::Routable;
+use axum::{body::Body, response::IntoResponse, Router};
+use http::{HeaderValue, Request, StatusCode, Uri};
+use std::{
+    collections::HashSet,
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+use tower_service::Service;
+
+const UNMATCHED: &str = "x-enum-router-unmatched";
+
+/// Sends a request for every sample through `router` and panics if one of
+/// them isn't routed to a handler, or if a route in `R::ROUTES` has no sample.
+pub async fn assert_routes_resolve<R: Routable>(
+    router: Router,
+    samples: impl IntoIterator<Item = R>,
+) {
+    let mut router = router
+        .fallback(not_found)
+        .method_not_allowed_fallback(method_not_allowed);
+
+    let mut covered = HashSet::new();
+    let mut failures = Vec::new();
+    for sample in samples {
+        let href = sample.url();
+        let method = sample.method();
+        covered.insert((method.to_string(), sample.pattern()));
+
+        let uri = href.router_uri();
+        let request = match uri.parse::<Uri>() {
+            Ok(uri) => Request::builder()
+                .method(method.clone())
+                .uri(uri)
+                .body(Body::empty())
+                .expect("method and uri are valid"),
+            Err(e) => {
+                failures.push(format!("{} {} is not a valid uri: {}", method, uri, e));
+                continue;
+            }
+        };
+
+        let response =
+            match std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx))
+                .await
+            {
+                Ok(()) => router.call(request).await,
+                Err(infallible) => match infallible {},
+            };
+        let response = match response {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        };
+        // the generated routers answer other methods with their own 405
+        if response.headers().contains_key(UNMATCHED)
+            || response.status() == StatusCode::METHOD_NOT_ALLOWED
+        {
+            failures.push(format!(
+                "{} {} ({}) does not resolve, router answered {}",
+                method,
+                uri,
+                sample.pattern(),
+                response.status()
+            ));
+        }
+    }
+
+    let missing = R::ROUTES
+        .iter()
+        .filter(|route| !covered.contains(&(route.method.to_owned(), route.pattern)))
+        .map(|route| {
+            format!(
+                "{} {} ({}::{}) has no sample",
+                route.method, route.pattern, route.enum_name, route.variant
+            )
+        });
+    failures.extend(missing);
+
+    if !failures.is_empty() {
+        panic!("routes do not resolve:\n{}", failures.join("\n"));
+    }
+}
+
+async fn not_found() -> impl IntoResponse {
+    (
+        StatusCode::NOT_FOUND,
+        [(UNMATCHED, HeaderValue::from_static("1"))],
+    )
+}
+
+async fn method_not_allowed() -> impl IntoResponse {
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        [(UNMATCHED, HeaderValue::from_static("1"))],
+    )
+}
+
+/// A sample of every route variant, for the test of `#[router(self_test)]`.
+///
+/// Implemented by `#[router(self_test)]` and `#[resource(self_test)]`, which
+/// need `self_test` on every nested enum too.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no samples for `self_test`",
+    note = "add `self_test` to the `#[router]` or `#[resource]` of `{Self}`"
+)]
+pub trait Samples: Sized {
+    fn samples() -> Vec<Self>;
+}
+
+/// Values used for route fields by `#[router(self_test)]`.
+///
+/// `#[query]` fields use `Default` instead.
+pub trait Sample {
+    fn sample() -> Self;
+}
+
+macro_rules! impl_sample {
+    ($value:expr => $($ty:ty),*) => {
+        $(
+            impl Sample for $ty {
+                fn sample() -> Self {
+                    $value
+                }
+            }
+        )*
+    };
+}
+
+impl_sample!(1 => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_sample!(1.0 => f32, f64);
+impl_sample!(true => bool);
+impl_sample!('a' => char);
+impl_sample!("sample".to_owned() => String);
+
+impl<T> Sample for Option<T> {
+    fn sample() -> Self {
+        None
+    }
+}
+
+/// Runs a future to completion on the current thread, for the generated
+/// `self_test` tests which can't assume an async runtime.
+#[doc(hidden)]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! `sitemap.xml` built from a route enum.
+//!
+//! ```rust,ignore
+//! #[router]
+//! enum Route {
+//!     #[get("/")]
+//!     #[sitemap(changefreq = "daily", priority = 1.0)]
+//!     Index,
+//!     #[get("/posts/{id}")]
+//!     Post(i64),
+//!     #[get("/sitemap.xml")]
+//!     #[sitemap(skip)]
+//!     Sitemap,
+//! }
+//!
+//! async fn sitemap(url_for: UrlFor) -> Response {
+//!     Sitemap::<Route>::new()
+//!         .values(|| async { post_ids().await.into_iter().map(Route::Post) })
+//!         .response(url_for.base())
+//!         .await
+//! }
+//! ```
+//!
+//! Every unit `GET` variant is included unless it has `#[sitemap(skip)]`,
+//! variants with fields are included for the values given to `values`.
+
+use crate::{html::escape, BaseUrl, Routable, UrlFor};
+use axum::{
+    extract::Request,
+    handler::Handler,
+    response::{IntoResponse, Response},
+};
+use axum_core::extract::FromRequestParts;
+use http::header;
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+
+/// `<changefreq>` and `<priority>` of a route, declared with
+/// `#[sitemap(changefreq = "weekly", priority = 0.5)]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    pub changefreq: Option<&'static str>,
+    /// The digits of the priority literal, e.g. `"0.5"`.
+    pub priority: Option<&'static str>,
+}
+
+type Values<R> = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Vec<R>> + Send>> + Send + Sync>;
+
+/// The routes of a sitemap, and an axum handler for `GET /sitemap.xml` with
+/// urls made absolute against the request's host.
+pub struct Sitemap<R> {
+    values: Vec<Values<R>>,
+}
+
+impl<R> Clone for Sitemap<R> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<R> fmt::Debug for Sitemap<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sitemap")
+            .field("values", &self.values.len())
+            .finish()
+    }
+}
+
+impl<R: Routable> Default for Sitemap<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Routable> Sitemap<R> {
+    /// A sitemap of the unit variants of `R`.
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    /// Adds the routes returned by `values`, which runs on every request,
+    /// e.g. the `Route::Post(id)` of every post in the database.
+    pub fn values<F, Fut, I>(mut self, values: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = I> + Send + 'static,
+        I: IntoIterator<Item = R>,
+    {
+        self.values.push(Arc::new(move || {
+            let values = values();
+            Box::pin(async move { values.await.into_iter().collect() })
+        }));
+        self
+    }
+
+    /// Every route in the sitemap, skipping routes with `#[sitemap(skip)]`
+    /// and routes that don't answer `GET`.
+    pub async fn routes(&self) -> Vec<(R, Options)> {
+        let mut routes = R::__sitemap_units();
+        for values in &self.values {
+            routes.extend(values().await);
+        }
+
+        routes
+            .into_iter()
+            .filter_map(|route| {
+                let options = route.sitemap_options()?;
+                Some((route, options))
+            })
+            .collect()
+    }
+
+    /// The `sitemap.xml` document, with urls made absolute against `base`.
+    pub async fn xml(&self, base: &BaseUrl) -> String {
+        let mut xml = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "\n",
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+            "\n"
+        ));
+        for (route, options) in self.routes().await {
+            xml.push_str("  <url>\n");
+            xml.push_str(&format!(
+                "    <loc>{}</loc>\n",
+                escape(base.url(&route).to_string())
+            ));
+            if let Some(changefreq) = options.changefreq {
+                xml.push_str(&format!("    <changefreq>{}</changefreq>\n", changefreq));
+            }
+            if let Some(priority) = options.priority {
+                xml.push_str(&format!("    <priority>{}</priority>\n", priority));
+            }
+            xml.push_str("  </url>\n");
+        }
+        xml.push_str("</urlset>\n");
+
+        xml
+    }
+
+    /// The `xml` as an `application/xml` response.
+    pub async fn response(&self, base: &BaseUrl) -> Response {
+        (
+            [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+            self.xml(base).await,
+        )
+            .into_response()
+    }
+}
+
+impl<R, S> Handler<(), S> for Sitemap<R>
+where
+    R: Routable + Send + Sync + 'static,
+    S: Send + Sync + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = Response> + Send>>;
+
+    fn call(self, request: Request, state: S) -> Self::Future {
+        Box::pin(async move {
+            let (mut parts, _body) = request.into_parts();
+            let url_for = match UrlFor::from_request_parts(&mut parts, &state).await {
+                Ok(url_for) => url_for,
+                Err(infallible) => match infallible {},
+            };
+
+            self.response(url_for.base()).await
+        })
+    }
+}
@@ -0,0 +1,84 @@
+//! Helpers for building test requests from route variants.
+//!
+//! ```rust,ignore
+//! use enum_router::testing::{oneshot, RequestExt};
+//!
+//! let response = oneshot(&Route::router(), Route::CreateTodo.request().json(&new_todo)).await;
+//! ```
+
+use crate::Routable;
+use axum::{body::Body, response::Response, Router};
+use http::{
+    header::{self, HeaderName, HeaderValue},
+    Request, Uri,
+};
+use serde::Serialize;
+use tower::ServiceExt;
+
+/// A request for `Route::router()` with the route's method and url and an
+/// empty body. The url has no base path, since the router matches paths
+/// without it.
+///
+/// Panics if the url isn't a valid uri.
+pub fn request(route: &impl Routable) -> Request<Body> {
+    let uri = route.url().router_uri();
+    let uri = Uri::try_from(&uri).unwrap_or_else(|e| panic!("{} is not a valid uri: {}", uri, e));
+
+    Request::builder()
+        .method(route.method())
+        .uri(uri)
+        .body(Body::empty())
+        .expect("method and uri are valid")
+}
+
+/// Builders for test requests.
+pub trait RequestExt {
+    /// Sets the body to `body` serialized as json.
+    fn json(self, body: &impl Serialize) -> Self;
+
+    /// Sets the body to `body` serialized as a urlencoded form.
+    fn form(self, body: &impl Serialize) -> Self;
+
+    fn header(self, name: impl TryInto<HeaderName>, value: impl TryInto<HeaderValue>) -> Self;
+}
+
+impl RequestExt for Request<Body> {
+    fn json(self, body: &impl Serialize) -> Self {
+        let body = serde_json::to_vec(body).expect("body serializes to json");
+        with_body(self, body, "application/json")
+    }
+
+    fn form(self, body: &impl Serialize) -> Self {
+        let body = serde_urlencoded::to_string(body).expect("body serializes to a form");
+        with_body(self, body, "application/x-www-form-urlencoded")
+    }
+
+    fn header(mut self, name: impl TryInto<HeaderName>, value: impl TryInto<HeaderValue>) -> Self {
+        let (Ok(name), Ok(value)) = (name.try_into(), value.try_into()) else {
+            panic!("invalid header");
+        };
+        self.headers_mut().append(name, value);
+        self
+    }
+}
+
+fn with_body(
+    request: Request<Body>,
+    body: impl Into<Body>,
+    content_type: &'static str,
+) -> Request<Body> {
+    let (mut parts, _) = request.into_parts();
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    Request::from_parts(parts, body.into())
+}
+
+/// Sends `request` through `router`.
+pub async fn oneshot(router: &Router, request: Request<Body>) -> Response {
+    match router.clone().oneshot(request).await {
+        Ok(response) => response,
+        Err(infallible) => match infallible {},
+    }
+}
@@ -0,0 +1,285 @@
+//! TypeScript url builders generated from the `ROUTES` of a route enum.
+
+use crate::RouteInfo;
+
+const RESERVED: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+];
+
+const QUERY_STRING: &str = r#"function queryString(query: Record<string, unknown>): string {
+  const params = Object.entries(query)
+    .filter(([, value]) => value !== undefined && value !== null)
+    .map(([key, value]) => `${encodeURIComponent(key)}=${encodeURIComponent(String(value))}`);
+  return params.length ? `?${params.join("&")}` : "";
+}
+"#;
+
+/// A TypeScript module with one url builder per route, and with `fetch`
+/// set, a typed `fetch` wrapper per route using its method.
+pub fn module(routes: &[RouteInfo], fetch: bool) -> String {
+    let mut module = String::from("// Generated by enum_router. Do not edit.\n\n");
+    module.push_str(QUERY_STRING);
+
+    for (route, name) in routes.iter().zip(function_names(routes)) {
+        let params = params(route);
+        let signature = params
+            .iter()
+            .map(|param| param.signature())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let args = params
+            .iter()
+            .map(|param| param.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        module.push('\n');
+        if !route.doc.is_empty() {
+            module.push_str(&format!(
+                "/** {} */\n",
+                route.doc.replace('\n', " ").replace("*/", "*\\/")
+            ));
+        }
+        module.push_str(&format!(
+            "export function {}({}): string {{\n  return {};\n}}\n",
+            name,
+            signature,
+            url(route, &params)
+        ));
+
+        if fetch {
+            let mut signature = signature;
+            let mut init = format!("{{ ...init, method: \"{}\" }}", route.method);
+            if let Some(body) = route.body {
+                signature = join(&signature, &format!("body: unknown /* {} */", body));
+                init = format!(
+                    "{{ ...init, method: \"{}\", headers: {{ \"content-type\": \"application/json\", ...init.headers }}, body: JSON.stringify(body) }}",
+                    route.method
+                );
+            }
+            signature = join(&signature, "init: RequestInit = {}");
+            module.push_str(&format!(
+                "\nexport function {}({}): Promise<Response> {{\n  return fetch({}({}), {});\n}}\n",
+                fetch_name(&name),
+                signature,
+                name,
+                args,
+                init
+            ));
+        }
+    }
+
+    module
+}
+
+struct Param {
+    name: String,
+    ty: String,
+    optional: bool,
+}
+
+impl Param {
+    fn signature(&self) -> String {
+        match self.optional {
+            true => format!("{}: {} = {{}}", self.name, self.ty),
+            false => format!("{}: {}", self.name, self.ty),
+        }
+    }
+}
+
+fn params(route: &RouteInfo) -> Vec<Param> {
+    let mut params = Vec::new();
+    let mut query = Vec::new();
+    for field in route.fields {
+        if field.query {
+            query.extend(field.fields);
+        } else if route.has_path_param(field.name) {
+            params.push(Param {
+                name: camel_case(field.name),
+                ty: ts_type(field.ty).0.to_owned(),
+                optional: false,
+            });
+        } else {
+            query.push(field);
+        }
+    }
+
+    if !query.is_empty() {
+        let optional = query.iter().all(|field| ts_type(field.ty).1);
+        let fields = query
+            .iter()
+            .map(|field| match ts_type(field.ty) {
+                (ty, true) => format!("{}?: {}", field.name, ty),
+                (ty, false) => format!("{}: {}", field.name, ty),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        params.push(Param {
+            name: "query".to_owned(),
+            ty: format!("{{ {} }}", fields),
+            optional,
+        });
+    }
+
+    params
+}
+
+fn url(route: &RouteInfo, params: &[Param]) -> String {
+    let path = route
+        .pattern
+        .split('/')
+        .map(
+            |part| match part.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+                Some(param) => match param.strip_prefix('*') {
+                    // a wildcard keeps its slashes
+                    Some(param) => format!(
+                        "${{String({}).split(\"/\").map(encodeURIComponent).join(\"/\")}}",
+                        camel_case(param)
+                    ),
+                    None => format!("${{encodeURIComponent(String({}))}}", camel_case(param)),
+                },
+                None => part.to_owned(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/");
+
+    match params.iter().any(|param| param.name == "query") {
+        true => format!("`{}` + queryString(query)", path),
+        false => format!("`{}`", path),
+    }
+}
+
+/// The TypeScript type for a rust type name, and whether it is optional.
+fn ts_type(ty: &str) -> (&'static str, bool) {
+    let (ty, optional) = match ty
+        .strip_prefix("Option<")
+        .and_then(|ty| ty.strip_suffix('>'))
+    {
+        Some(ty) => (ty, true),
+        None => (ty, false),
+    };
+    let ty = match ty {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" | "f32" | "f64" => "number",
+        "bool" => "boolean",
+        _ => "string",
+    };
+
+    (ty, optional)
+}
+
+/// The function name of every route. Handlers of nested enums whose names
+/// are taken by another route get the enum name as a prefix, e.g. `index` of
+/// a nested `Admin` becomes `adminIndex`.
+///
+/// Panics when two routes would still export the same function.
+fn function_names(routes: &[RouteInfo]) -> Vec<String> {
+    let names = routes.iter().map(function_name).collect::<Vec<_>>();
+    let top = routes.first().map(|route| route.enum_name);
+    let names = routes
+        .iter()
+        .zip(&names)
+        .map(|(route, name)| {
+            let taken = names.iter().filter(|other| *other == name).count() > 1;
+            match taken && Some(route.enum_name) != top {
+                true => format!(
+                    "{}{}",
+                    camel_case(route.enum_name),
+                    capitalize(name.trim_end_matches('_'))
+                ),
+                false => name.clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for (i, name) in names.iter().enumerate() {
+        assert!(
+            !names[..i].contains(name),
+            "two routes would export `{}`, give one of their handlers another name",
+            name
+        );
+    }
+
+    names
+}
+
+fn function_name(route: &RouteInfo) -> String {
+    let name = camel_case(&route.handler.replace("::", "_"));
+    match RESERVED.contains(&name.as_str()) {
+        true => format!("{}_", name),
+        false => name,
+    }
+}
+
+fn fetch_name(name: &str) -> String {
+    format!("fetch{}", capitalize(name.trim_end_matches('_')))
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}", first.to_ascii_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+fn camel_case(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut upper = false;
+    for (i, c) in s.chars().enumerate() {
+        match c {
+            '_' => upper = i > 0,
+            c if upper => {
+                result.push(c.to_ascii_uppercase());
+                upper = false;
+            }
+            c if result.is_empty() => result.push(c.to_ascii_lowercase()),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+fn join(a: &str, b: &str) -> String {
+    match a.is_empty() {
+        true => b.to_owned(),
+        false => format!("{}, {}", a, b),
+    }
+}
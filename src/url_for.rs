@@ -0,0 +1,168 @@
+use crate::{Href, Routable};
+use axum_core::extract::FromRequestParts;
+use http::{header, request::Parts, HeaderMap};
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// The scheme and authority absolute urls are generated against.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BaseUrl {
+    scheme: String,
+    authority: String,
+}
+
+impl BaseUrl {
+    pub fn new(scheme: impl Into<String>, authority: impl Into<String>) -> Self {
+        Self {
+            scheme: scheme.into(),
+            authority: authority.into().trim_end_matches('/').to_owned(),
+        }
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Host and optional port, e.g. `"example.com:8080"`.
+    pub fn authority(&self) -> &str {
+        &self.authority
+    }
+
+    pub fn host(&self) -> &str {
+        self.split_authority().0
+    }
+
+    pub fn port(&self) -> Option<u16> {
+        self.split_authority().1.and_then(|port| port.parse().ok())
+    }
+
+    fn split_authority(&self) -> (&str, Option<&str>) {
+        match self.authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => (host, Some(port)),
+            _ => (&self.authority, None),
+        }
+    }
+
+    pub fn url(&self, route: &impl Routable) -> Href {
+        route.url().absolute(self)
+    }
+
+    fn from_parts(parts: &Parts) -> Self {
+        let headers = &parts.headers;
+        let forwarded = headers
+            .get(header::FORWARDED)
+            .and_then(|value| value.to_str().ok())
+            .map(forwarded)
+            .unwrap_or_default();
+
+        let scheme = forwarded
+            .proto
+            .or_else(|| first_header(headers, "x-forwarded-proto"))
+            .or_else(|| parts.uri.scheme_str().map(str::to_owned))
+            .unwrap_or_else(|| "http".to_owned());
+
+        let authority = forwarded
+            .host
+            .or_else(|| {
+                first_header(headers, "x-forwarded-host").map(|host| {
+                    match first_header(headers, "x-forwarded-port") {
+                        Some(port) if !host.contains(':') => format!("{}:{}", host, port),
+                        _ => host,
+                    }
+                })
+            })
+            .or_else(|| first_header(headers, header::HOST.as_str()))
+            .or_else(|| parts.uri.authority().map(|a| a.to_string()))
+            .unwrap_or_else(|| "localhost".to_owned());
+
+        Self::new(scheme, authority)
+    }
+}
+
+impl fmt::Display for BaseUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.authority)
+    }
+}
+
+impl FromStr for BaseUrl {
+    type Err = InvalidBaseUrl;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once("://") {
+            Some((scheme, authority)) if !scheme.is_empty() && !authority.is_empty() => {
+                Ok(Self::new(scheme, authority))
+            }
+            _ => Err(InvalidBaseUrl(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidBaseUrl(String);
+
+impl fmt::Display for InvalidBaseUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid base url {:?}, expected scheme://host", self.0)
+    }
+}
+
+impl std::error::Error for InvalidBaseUrl {}
+
+/// Extractor that renders route variants as absolute urls for the current
+/// request, honoring `Forwarded` and `X-Forwarded-*` headers set by proxies.
+#[derive(Clone, Debug)]
+pub struct UrlFor(pub BaseUrl);
+
+impl UrlFor {
+    pub fn url(&self, route: &impl Routable) -> Href {
+        self.0.url(route)
+    }
+
+    pub fn base(&self) -> &BaseUrl {
+        &self.0
+    }
+}
+
+impl<S> FromRequestParts<S> for UrlFor
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(BaseUrl::from_parts(parts)))
+    }
+}
+
+#[derive(Default)]
+struct Forwarded {
+    proto: Option<String>,
+    host: Option<String>,
+}
+
+fn forwarded(value: &str) -> Forwarded {
+    let first = value.split(',').next().unwrap_or_default();
+    let mut forwarded = Forwarded::default();
+    for pair in first.split(';') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_owned();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "proto" => forwarded.proto = Some(value),
+            "host" => forwarded.host = Some(value),
+            _ => {}
+        }
+    }
+
+    forwarded
+}
+
+fn first_header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
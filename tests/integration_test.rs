@@ -23,7 +23,7 @@ async fn login() -> impl IntoResponse {
     "login"
 }
 
-#[derive(PartialEq, Debug, QueryString, Deserialize)]
+#[derive(PartialEq, Debug, QueryString, FromQueryString, Deserialize)]
 pub struct Abc {
     abc: Option<u8>,
 }
@@ -47,10 +47,38 @@ pub enum Route {
     Login,
     #[get("/abc")]
     Abc(#[query] Abc),
+    /// Shows xyz.
     #[get("/xyz/{xyz}")]
     Xyz(String),
 }
 
+#[allow(unused)]
+#[router(self_test)]
+enum Shop {
+    #[get("/shop")]
+    Home,
+    #[get("/shop/search")]
+    Search { q: Option<String> },
+    #[router]
+    Products(Products),
+}
+
+async fn home() {}
+
+async fn search() {}
+
+#[resource(self_test)]
+pub enum Products {
+    Index,
+    Show(i64),
+}
+
+impl Products {
+    async fn index() {}
+
+    async fn show(Path(_id): Path<i64>) {}
+}
+
 #[tokio::test]
 async fn it_works() -> Result<()> {
     let app = Route::router();
@@ -99,6 +127,7 @@ async fn resource_routing_works() -> Result<()> {
             Self::Index.to_string()
         }
 
+        #[allow(clippy::new_ret_no_self)]
         async fn new() -> String {
             Self::New.to_string()
         }
@@ -208,6 +237,1759 @@ async fn merge_routing_works() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn href_works() -> Result<()> {
+    use axum::http::{HeaderValue, Method, Uri};
+
+    let href = Route::Xyz("1".into()).url();
+    assert_eq!(href.path(), "/xyz/1");
+    assert_eq!(href.query(), None);
+    assert_eq!(href.method(), &Method::GET);
+    assert_eq!(Route::Login.method(), Method::POST);
+
+    let href = Route::Abc(Abc { abc: Some(1) }).url();
+    assert_eq!(href.path(), "/abc");
+    assert_eq!(href.query(), Some("abc=1"));
+
+    let href = href
+        .with_param("q", "a b&c")
+        .with_fragment("top")
+        .absolute("https://example.com/");
+    assert_eq!(href, "https://example.com/abc?abc=1&q=a+b%26c#top");
+    assert_eq!(
+        Uri::try_from(&href)?,
+        "https://example.com/abc?abc=1&q=a+b%26c#top"
+    );
+    assert_eq!(HeaderValue::try_from(href)?, "https://example.com/abc?abc=1&q=a+b%26c#top");
+
+    Ok(())
+}
+
+#[test]
+fn redirect_works() {
+    use axum::http::header::LOCATION;
+
+    let response = Route::Login.redirect();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[LOCATION], "/login");
+
+    let response = Route::Xyz("1".into()).redirect_permanent();
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(response.headers()[LOCATION], "/xyz/1");
+
+    let response = Route::Index.redirect_temporary();
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    let response = Route::LoginForm.htmx_redirect();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["hx-redirect"], "/login");
+
+    let response = Route::LoginForm.htmx_location();
+    assert_eq!(response.headers()["hx-location"], "/login");
+
+    let response = Route::Index.into_response();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[LOCATION], "/");
+}
+
+#[tokio::test]
+async fn absolute_url_works() -> Result<()> {
+    let base: BaseUrl = "https://example.com/".parse()?;
+    assert_eq!(
+        Route::Xyz("1".into()).absolute_url(&base),
+        "https://example.com/xyz/1"
+    );
+
+    async fn handler(url_for: UrlFor) -> String {
+        url_for.url(&Route::LoginForm).to_string()
+    }
+
+    let app: Router = Router::new().route("/", axum::routing::get(handler));
+
+    let response = app.clone().oneshot(
+        Request::builder()
+            .uri("/")
+            .header("host", "localhost:3000")
+            .body(Body::empty())?,
+    );
+    assert_eq!(body(response.await?).await, "http://localhost:3000/login");
+
+    let response = app.clone().oneshot(
+        Request::builder()
+            .uri("/")
+            .header("host", "localhost:3000")
+            .header("x-forwarded-proto", "https")
+            .header("x-forwarded-host", "example.com")
+            .body(Body::empty())?,
+    );
+    assert_eq!(body(response.await?).await, "https://example.com/login");
+
+    let response = app.oneshot(
+        Request::builder()
+            .uri("/")
+            .header("forwarded", "for=1.2.3.4;proto=https;host=\"example.org:8443\"")
+            .body(Body::empty())?,
+    );
+    assert_eq!(body(response.await?).await, "https://example.org:8443/login");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn base_path_works() -> Result<()> {
+    #[router(base_path = "/app")]
+    enum Mounted {
+        #[get("/")]
+        Home,
+        #[get("/todos/{id}")]
+        Todo(i64),
+    }
+
+    async fn home() -> String {
+        Mounted::Home.to_string()
+    }
+
+    async fn todo(Path(id): Path<i64>) -> String {
+        Mounted::Todo(id).to_string()
+    }
+
+    assert_eq!(Mounted::Todo(1).to_string(), "/app/todos/1");
+    assert_eq!(Mounted::Todo(1).url().path(), "/todos/1");
+    assert_eq!(Mounted::Home.redirect().headers()["location"], "/app/");
+
+    let url = enum_router::base_path::scope("/proxy", async { Route::Login.to_string() }).await;
+    assert_eq!(url, "/proxy/login");
+    assert_eq!(Route::Login.to_string(), "/login");
+
+    let app = Mounted::router();
+    assert_eq!(StatusCode::OK, make_request(&app, "GET", "/todos/1").await);
+
+    let app = app.layer(axum::middleware::from_fn(
+        enum_router::base_path::forwarded_prefix,
+    ));
+    let response = app.clone().oneshot(
+        Request::builder()
+            .uri("/todos/2")
+            .header("x-forwarded-prefix", "/proxy/")
+            .body(Body::empty())?,
+    );
+    assert_eq!(body(response.await?).await, "/proxy/todos/2");
+
+    for prefix in ["\\evil.com", "/a/../b", "/a b", "/a%2f"] {
+        let response = app.clone().oneshot(
+            Request::builder()
+                .uri("/todos/2")
+                .header("x-forwarded-prefix", prefix)
+                .body(Body::empty())?,
+        );
+        assert_eq!(body(response.await?).await, "/app/todos/2", "{}", prefix);
+    }
+
+    let href = enum_router::Href::new(axum::http::Method::GET, "/x");
+    assert_eq!(href.clone().with_base_path("app").to_string(), "/app/x");
+    assert_eq!(href.clone().with_base_path("/a/b/").to_string(), "/a/b/x");
+    assert_eq!(href.with_base_path("\\evil.com").to_string(), "/x");
+
+    Ok(())
+}
+
+#[test]
+#[allow(dead_code)]
+fn routes_const_works() {
+    #[router]
+    enum App {
+        #[get("/")]
+        Index,
+        #[router]
+        Sessions(Sessions),
+    }
+
+    async fn index() {}
+
+    #[resource]
+    pub enum Sessions {
+        Index,
+        /// Edit a session.
+        Edit(i64),
+    }
+
+    impl Sessions {
+        async fn index() {}
+        async fn edit() {}
+    }
+
+    let routes = App::ROUTES
+        .iter()
+        .map(|r| (r.method, r.pattern, r.enum_name, r.variant, r.handler))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        routes,
+        vec![
+            ("GET", "/", "App", "Index", "index"),
+            ("GET", "/sessions", "Sessions", "Index", "Sessions::index"),
+            ("GET", "/sessions/{id}/edit", "Sessions", "Edit", "Sessions::edit"),
+        ]
+    );
+    assert_eq!(App::ROUTES[2].doc, "Edit a session.");
+    assert_eq!(
+        App::ROUTES[2].fields,
+        &[FieldInfo {
+            name: "id",
+            ty: "i64",
+            query: false,
+            fields: &[]
+        }]
+    );
+
+    assert!(Route::ROUTES[3].fields[0].query);
+    assert_eq!(Route::ROUTES[4].doc, "Shows xyz.");
+    assert_eq!(Route::ROUTES[4].fields[0].name, "xyz");
+    assert_eq!(Route::Xyz("1".into()).pattern(), "/xyz/{xyz}");
+    assert_eq!(App::Sessions(Sessions::Edit(1)).pattern(), "/sessions/{id}/edit");
+}
+
+#[test]
+#[allow(dead_code)]
+fn routes_table_works() {
+    #[router]
+    enum App {
+        #[get("/")]
+        Index,
+        #[router]
+        Sessions(Sessions),
+    }
+
+    async fn index() {}
+
+    #[resource]
+    pub enum Sessions {
+        New,
+        Edit(i64),
+    }
+
+    impl Sessions {
+        #[allow(clippy::new_ret_no_self)]
+        async fn new() {}
+        async fn edit() {}
+    }
+
+    assert_eq!(
+        App::routes_table(),
+        "\
+Method  Pattern              Route                          Handler
+GET     /                    App::Index                     index
+GET     /sessions/new        App::Sessions(Sessions::New)   Sessions::new
+GET     /sessions/{id}/edit  App::Sessions(Sessions::Edit)  Sessions::edit
+"
+    );
+}
+
+#[test]
+#[allow(dead_code)]
+fn body_and_response_types_work() {
+    use axum::{Form, Json};
+
+    #[derive(Deserialize)]
+    struct NewTodo {}
+
+    struct Todo {}
+
+    #[router]
+    enum Todos {
+        #[post("/todos", body = NewTodo, response = Todo, check_body)]
+        Create,
+        #[put("/todos/{id}", body = NewTodo, check_body)]
+        Update(i64),
+        #[get("/todos/{id}", response = Todo)]
+        Show(i64),
+    }
+
+    async fn create(Json(_todo): Json<NewTodo>) {}
+
+    async fn update(Path(_id): Path<i64>, Form(_todo): Form<NewTodo>) {}
+
+    async fn show(Path(_id): Path<i64>) {}
+
+    assert_eq!(Todos::ROUTES[0].body, Some("NewTodo"));
+    assert_eq!(Todos::ROUTES[0].response, Some("Todo"));
+    assert_eq!(Todos::ROUTES[1].body, Some("NewTodo"));
+    assert_eq!(Todos::ROUTES[2].body, None);
+    assert_eq!(Todos::ROUTES[2].response, Some("Todo"));
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn openapi_works() {
+    let doc = Route::openapi();
+
+    assert_eq!(doc["openapi"], "3.1.0");
+    assert_eq!(doc["info"]["title"], "enum_router");
+    assert_eq!(doc["paths"]["/login"]["get"]["operationId"], "login_form");
+    assert!(doc["paths"]["/login"]["post"].is_object());
+
+    let xyz = &doc["paths"]["/xyz/{xyz}"]["get"];
+    assert_eq!(xyz["summary"], "Shows xyz.");
+    assert_eq!(
+        xyz["parameters"][0],
+        serde_json::json!({
+            "name": "xyz",
+            "in": "path",
+            "required": true,
+            "schema": { "type": "string" },
+        })
+    );
+
+    let abc = &doc["paths"]["/abc"]["get"];
+    assert_eq!(
+        abc["parameters"][0],
+        serde_json::json!({
+            "name": "abc",
+            "in": "query",
+            "required": false,
+            "schema": { "type": "integer", "format": "int32" },
+        })
+    );
+
+    #[router]
+    #[allow(unused)]
+    enum Static {
+        #[get("/files/{*rest}")]
+        Files(String),
+        #[any("/webhook")]
+        Webhook,
+    }
+
+    async fn files() {}
+    async fn webhook() {}
+
+    let doc = Static::openapi();
+    assert_eq!(
+        doc["paths"]["/files/{rest}"]["get"]["parameters"][0]["name"],
+        "rest"
+    );
+    assert_eq!(doc["paths"]["/files/{rest}"]["get"]["parameters"][0]["in"], "path");
+    assert_eq!(doc["paths"]["/webhook"]["get"]["operationId"], "webhook");
+    assert_eq!(doc["paths"]["/webhook"]["post"]["operationId"], "webhook_post");
+
+    let mut ids = doc["paths"]
+        .as_object()
+        .unwrap()
+        .values()
+        .flat_map(|path| path.as_object().unwrap().values())
+        .map(|operation| operation["operationId"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    let count = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), count);
+}
+
+#[cfg(feature = "typescript")]
+#[test]
+fn typescript_works() {
+    let ts = Route::typescript();
+
+    assert!(ts.contains(
+        "export function loginForm(): string {\n  return `/login`;\n}\n"
+    ));
+    assert!(ts.contains(
+        "export function abc(query: { abc?: number } = {}): string {\n  return `/abc` + queryString(query);\n}\n"
+    ));
+    assert!(ts.contains(
+        "/** Shows xyz. */\nexport function xyz(xyz: string): string {\n  return `/xyz/${encodeURIComponent(String(xyz))}`;\n}\n"
+    ));
+    assert!(!ts.contains("fetch"));
+
+    let ts = Route::typescript_client();
+    assert!(ts.contains(
+        "export function fetchLogin(init: RequestInit = {}): Promise<Response> {\n  return fetch(login(), { ...init, method: \"POST\" });\n}\n"
+    ));
+
+    #[router]
+    #[allow(unused)]
+    enum Static {
+        #[get("/files/{*rest}")]
+        Files(String),
+    }
+
+    async fn files() {}
+
+    assert!(Static::typescript().contains(
+        "export function files(rest: string): string {\n  return `/files/${String(rest).split(\"/\").map(encodeURIComponent).join(\"/\")}`;\n}\n"
+    ));
+
+    mod admin {
+        use enum_router::router;
+
+        #[router]
+        #[allow(unused)]
+        pub enum Admin {
+            #[get("/admin")]
+            Index,
+        }
+
+        pub async fn index() {}
+    }
+
+    #[router]
+    #[allow(unused)]
+    enum App {
+        #[get("/")]
+        Index,
+        #[router]
+        Admin(admin::Admin),
+    }
+
+    async fn index() {}
+
+    let ts = App::typescript_client();
+    assert_eq!(ts.matches("export function index(").count(), 1);
+    assert_eq!(ts.matches("export function adminIndex(").count(), 1);
+    assert!(ts.contains("export function fetchAdminIndex(init: RequestInit = {}): Promise<Response> {\n  return fetch(adminIndex(), { ...init, method: \"GET\" });\n}\n"));
+}
+
+#[cfg(feature = "client")]
+#[tokio::test]
+async fn client_works() -> Result<()> {
+    use axum::{Form, Json};
+    use enum_router::client::Client;
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize)]
+    struct NewTodo {
+        title: String,
+    }
+
+    #[router]
+    enum Todos {
+        #[get("/todos/{id}/edit")]
+        EditTodo(i64),
+        #[post("/todos", body = NewTodo)]
+        CreateTodo,
+        #[put("/todos/{id}", body = NewTodo, check_body)]
+        Update(i64),
+    }
+
+    async fn edit_todo(Path(id): Path<i64>) -> String {
+        Todos::EditTodo(id).to_string()
+    }
+
+    async fn create_todo(Json(todo): Json<NewTodo>) -> String {
+        todo.title
+    }
+
+    async fn update(Path(id): Path<i64>, Form(todo): Form<NewTodo>) -> String {
+        format!("{} {}", id, todo.title)
+    }
+
+    let client = Client::new(Todos::router());
+
+    let response = client.call(Todos::EditTodo(1)).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "/todos/1/edit");
+
+    let response = client.call(Todos::CreateTodo).await?;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let todo = NewTodo {
+        title: "write tests".into(),
+    };
+    let response = client.call_with(Todos::CreateTodo, &todo).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "write tests");
+
+    let error = client
+        .call_with(Todos::CreateTodo, &serde_json::json!({ "title": "untyped" }))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        enum_router::client::ClientError::BodyType { expected: "NewTodo", .. }
+    ));
+
+    let response = client.call_form(Todos::Update(2), &todo).await?;
+    assert_eq!(body(response).await, "2 write tests");
+    let response = client.call_with(Todos::Update(2), &todo).await?;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    #[router(base_path = "/app")]
+    #[allow(unused)]
+    enum Mounted {
+        #[get("/todos/{id}")]
+        ShowTodo(i64),
+    }
+
+    async fn show_todo(Path(id): Path<i64>) -> String {
+        id.to_string()
+    }
+
+    let response = Client::new(Mounted::router())
+        .call(Mounted::ShowTodo(1))
+        .await?;
+    assert_eq!(body(response).await, "1");
+
+    Ok(())
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_requests_work() {
+    use axum::{Form, Json};
+    use enum_router::testing::{oneshot, RequestExt};
+    use serde::Serialize;
+
+    let app = Route::router();
+    let response = oneshot(&app, Route::LoginForm.request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = oneshot(&app, Route::Login.request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = oneshot(&app, Route::Abc(Abc { abc: Some(1) }).request()).await;
+    assert_eq!(body(response).await, "/abc?abc=1");
+
+    #[derive(Serialize, Deserialize)]
+    struct Todo {
+        title: String,
+    }
+
+    #[router]
+    enum Todos {
+        #[post("/todos")]
+        CreateTodo,
+        #[put("/todos/{id}")]
+        UpdateTodo(i64),
+    }
+
+    async fn create_todo(Json(todo): Json<Todo>) -> String {
+        todo.title
+    }
+
+    async fn update_todo(
+        headers: axum::http::HeaderMap,
+        Path(id): Path<i64>,
+        Form(todo): Form<Todo>,
+    ) -> String {
+        format!("{} {} {:?}", id, todo.title, headers["x-test"])
+    }
+
+    let todo = Todo {
+        title: "write tests".into(),
+    };
+    let app = Todos::router();
+    let request = Todos::CreateTodo.request();
+    assert_eq!(request.method(), "POST");
+    assert_eq!(request.uri(), "/todos");
+
+    let response = oneshot(&app, request.json(&todo)).await;
+    assert_eq!(body(response).await, "write tests");
+
+    let request = Todos::UpdateTodo(2).request().form(&todo).header("x-test", "1");
+    let response = oneshot(&app, request).await;
+    assert_eq!(body(response).await, "2 write tests \"1\"");
+
+    #[router(base_path = "/app")]
+    #[allow(unused)]
+    enum Mounted {
+        #[get("/todos/{id}")]
+        ShowTodo(i64),
+    }
+
+    async fn show_todo(Path(id): Path<i64>) -> String {
+        id.to_string()
+    }
+
+    assert_eq!(Mounted::ShowTodo(1).to_string(), "/app/todos/1");
+    let request = Mounted::ShowTodo(1).request();
+    assert_eq!(request.uri(), "/todos/1");
+    let response = oneshot(&Mounted::router(), request).await;
+    assert_eq!(body(response).await, "1");
+}
+
+#[tokio::test]
+async fn routes_resolve_works() {
+    Route::assert_all_routes_resolve([
+        Route::Index,
+        Route::LoginForm,
+        Route::Login,
+        Route::Abc(Abc { abc: Some(1) }),
+        Route::Xyz("xyz".into()),
+    ])
+    .await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "POST /login (Route::Login) has no sample")]
+async fn routes_resolve_requires_every_route() {
+    Route::assert_all_routes_resolve([Route::Index, Route::LoginForm]).await;
+}
+
+#[test]
+fn route_names_work() {
+    #[router]
+    #[allow(unused)]
+    enum App {
+        #[get("/todos/{id}/edit")]
+        EditTodo(i64),
+        #[post("/todos", name = "todos.create")]
+        CreateTodo,
+        #[router]
+        Sessions(Sessions),
+    }
+
+    async fn edit_todo() {}
+    async fn create_todo() {}
+
+    #[resource]
+    #[allow(unused)]
+    enum Sessions {
+        Index,
+        Edit(i64),
+    }
+
+    impl Sessions {
+        async fn index() {}
+        async fn edit() {}
+    }
+
+    assert_eq!(App::EditTodo(5).name(), "edit_todo");
+    assert_eq!(App::CreateTodo.name(), "todos.create");
+    assert_eq!(App::Sessions(Sessions::Edit(1)).name(), "sessions.edit");
+    assert_eq!(Shop::Products(Products::Index).name(), "products.index");
+
+    let names = App::ROUTES.iter().map(|r| r.name).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec!["edit_todo", "todos.create", "sessions.index", "sessions.edit"]
+    );
+}
+
+#[tokio::test]
+async fn nested_route_names_work() -> Result<()> {
+    use enum_router::MatchedRoute;
+
+    #[router]
+    #[allow(unused)]
+    enum App {
+        #[get("/")]
+        Index,
+        #[router]
+        Admin(Admin),
+    }
+
+    #[router]
+    #[allow(unused)]
+    enum Admin {
+        #[get("/admin")]
+        Index,
+        #[get("/admin/users", name = "users")]
+        Users,
+    }
+
+    async fn index(route: MatchedRoute) -> &'static str {
+        route.name
+    }
+
+    async fn users(route: MatchedRoute) -> &'static str {
+        route.name
+    }
+
+    assert_eq!(App::Index.name(), "index");
+    assert_eq!(App::Admin(Admin::Index).name(), "admin.index");
+    assert_eq!(App::Admin(Admin::Users).name(), "users");
+    assert_eq!(Admin::Index.name(), "index");
+
+    let names = App::ROUTES.iter().map(|r| r.name).collect::<Vec<_>>();
+    assert_eq!(names, vec!["index", "admin.index", "users"]);
+
+    let response = App::router().oneshot(request("GET", "/admin")).await?;
+    assert_eq!(body(response).await, "admin.index");
+
+    #[cfg(feature = "openapi")]
+    {
+        let doc = App::openapi();
+        assert_eq!(doc["paths"]["/"]["get"]["operationId"], "index");
+        assert_eq!(doc["paths"]["/admin"]["get"]["operationId"], "admin.index");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn layers_work() {
+    use axum::{extract::Request, middleware::from_fn, middleware::Next, response::Response};
+    use enum_router::MatchedRoute;
+
+    #[router]
+    #[layer(from_fn(trace))]
+    #[allow(unused)]
+    enum App {
+        #[get("/")]
+        Index,
+        #[get("/admin")]
+        #[layer(from_fn(require_admin))]
+        #[layer(from_fn(trace))]
+        Admin,
+        #[router]
+        #[layer(from_fn(require_admin))]
+        Reports(Reports),
+    }
+
+    async fn index() -> &'static str {
+        "index"
+    }
+
+    async fn admin() -> &'static str {
+        "admin"
+    }
+
+    #[resource]
+    #[layer(from_fn(trace))]
+    #[allow(unused)]
+    enum Reports {
+        Index,
+        #[layer(from_fn(trace))]
+        Show(i64),
+    }
+
+    impl Reports {
+        async fn index() -> &'static str {
+            "reports"
+        }
+
+        async fn show() -> &'static str {
+            "report"
+        }
+    }
+
+    async fn require_admin(request: Request, next: Next) -> Response {
+        match request.headers().contains_key("x-admin") {
+            true => next.run(request).await,
+            false => StatusCode::FORBIDDEN.into_response(),
+        }
+    }
+
+    // appends the matched route of every layer that ran
+    async fn trace(mut request: Request, next: Next) -> Response {
+        let route = request
+            .extensions()
+            .get::<MatchedRoute>()
+            .map(|route| route.name)
+            .unwrap_or("none");
+        let trace = match request.headers().get("x-trace") {
+            Some(trace) => format!("{},{}", trace.to_str().unwrap(), route),
+            None => route.to_owned(),
+        };
+        request
+            .headers_mut()
+            .insert("x-trace", trace.parse().unwrap());
+        let mut response = next.run(request).await;
+        if !response.headers().contains_key("x-trace") {
+            response
+                .headers_mut()
+                .insert("x-trace", trace.parse().unwrap());
+        }
+        response
+    }
+
+    let app = App::router();
+    let response = app.clone().oneshot(request("GET", "/")).await.unwrap();
+    assert_eq!(response.headers()["x-trace"], "index");
+    assert_eq!(body(response).await, "index");
+
+    let response = app.clone().oneshot(request("GET", "/admin")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.headers()["x-trace"], "admin");
+
+    let mut admin_request = request("GET", "/admin");
+    admin_request
+        .headers_mut()
+        .insert("x-admin", "1".parse().unwrap());
+    let response = app.clone().oneshot(admin_request).await.unwrap();
+    assert_eq!(response.headers()["x-trace"], "admin,admin");
+    assert_eq!(body(response).await, "admin");
+
+    let response = app.clone().oneshot(request("GET", "/reports")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.headers()["x-trace"], "reports.index");
+
+    let mut admin_request = request("GET", "/reports/1");
+    admin_request
+        .headers_mut()
+        .insert("x-admin", "1".parse().unwrap());
+    let response = app.oneshot(admin_request).await.unwrap();
+    assert_eq!(
+        response.headers()["x-trace"],
+        "reports.show,reports.show,reports.show"
+    );
+    assert_eq!(body(response).await, "report");
+}
+
+#[tokio::test]
+async fn guards_work() {
+    use axum::{extract::State, http::HeaderMap};
+
+    #[derive(Clone)]
+    struct AppState {
+        owner: &'static str,
+    }
+
+    #[router(AppState)]
+    #[allow(unused)]
+    enum App {
+        #[get("/")]
+        Index,
+        #[get("/billing", guard = is_signed_in, guard = is_owner)]
+        Billing,
+    }
+
+    async fn index() -> &'static str {
+        "index"
+    }
+
+    async fn billing(State(state): State<AppState>) -> &'static str {
+        state.owner
+    }
+
+    async fn is_signed_in(headers: HeaderMap) -> std::result::Result<(), StatusCode> {
+        match headers.contains_key("x-user") {
+            true => Ok(()),
+            false => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+
+    async fn is_owner(
+        State(state): State<AppState>,
+        headers: HeaderMap,
+    ) -> std::result::Result<(), (StatusCode, &'static str)> {
+        match headers.get("x-user").and_then(|user| user.to_str().ok()) == Some(state.owner) {
+            true => Ok(()),
+            false => Err((StatusCode::FORBIDDEN, "not the owner")),
+        }
+    }
+
+    let app = App::router().with_state(AppState { owner: "alice" });
+    let user_request = |user: &str| {
+        let mut request = request("GET", "/billing");
+        request
+            .headers_mut()
+            .insert("x-user", user.parse().unwrap());
+        request
+    };
+
+    assert_eq!(make_request(&app, "GET", "/").await, StatusCode::OK);
+    assert_eq!(
+        make_request(&app, "GET", "/billing").await,
+        StatusCode::UNAUTHORIZED
+    );
+    let response = app.clone().oneshot(user_request("bob")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(body(response).await, "not the owner");
+    let response = app.oneshot(user_request("alice")).await.unwrap();
+    assert_eq!(body(response).await, "alice");
+
+    assert_eq!(App::Index.guards(), &[] as &[&str]);
+    assert_eq!(App::Billing.guards(), &["is_signed_in", "is_owner"]);
+    let unguarded = App::ROUTES
+        .iter()
+        .filter(|route| route.guards.is_empty())
+        .map(|route| route.pattern)
+        .collect::<Vec<_>>();
+    assert_eq!(unguarded, vec!["/"]);
+}
+
+#[tokio::test]
+async fn multiple_methods_work() {
+    use axum::http::Method;
+
+    #[router]
+    #[allow(unused)]
+    enum App {
+        #[route(GET, HEAD, "/feed")]
+        Feed,
+        #[route(PUT, POST, "/items/{id}", name = "items.save")]
+        SaveItem(i64),
+        #[any("/webhook")]
+        Webhook,
+        #[options("/cors")]
+        Cors,
+    }
+
+    async fn feed() -> &'static str {
+        "feed"
+    }
+
+    async fn save_item(Path(id): Path<i64>) -> String {
+        id.to_string()
+    }
+
+    async fn webhook(method: Method) -> String {
+        method.to_string()
+    }
+
+    async fn cors() {}
+
+    assert_eq!(App::Feed.method(), Method::GET);
+    assert_eq!(App::SaveItem(1).method(), Method::PUT);
+    assert_eq!(App::SaveItem(1).to_string(), "/items/1");
+    assert_eq!(App::Webhook.method(), Method::GET);
+    assert_eq!(App::Cors.method(), Method::OPTIONS);
+
+    let methods = App::ROUTES
+        .iter()
+        .map(|route| (route.method, route.methods))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        methods,
+        vec![
+            ("GET", &["GET", "HEAD"][..]),
+            ("PUT", &["PUT", "POST"][..]),
+            ("GET", &["*"][..]),
+            ("OPTIONS", &["OPTIONS"][..]),
+        ]
+    );
+    assert_eq!(
+        App::routes_table(),
+        "Method    Pattern      Route          Handler\n\
+         GET,HEAD  /feed        App::Feed      feed\n\
+         PUT,POST  /items/{id}  App::SaveItem  save_item\n\
+         *         /webhook     App::Webhook   webhook\n\
+         OPTIONS   /cors        App::Cors      cors\n"
+    );
+
+    let app = App::router();
+    assert_eq!(make_request(&app, "GET", "/feed").await, StatusCode::OK);
+    assert_eq!(make_request(&app, "HEAD", "/feed").await, StatusCode::OK);
+    assert_eq!(
+        make_request(&app, "POST", "/feed").await,
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+    let response = app.clone().oneshot(request("POST", "/items/2")).await.unwrap();
+    assert_eq!(body(response).await, "2");
+    assert_eq!(make_request(&app, "PUT", "/items/2").await, StatusCode::OK);
+    let response = app.clone().oneshot(request("DELETE", "/webhook")).await.unwrap();
+    assert_eq!(body(response).await, "DELETE");
+    assert_eq!(make_request(&app, "OPTIONS", "/cors").await, StatusCode::OK);
+
+    App::assert_all_routes_resolve([App::Feed, App::SaveItem(1), App::Webhook, App::Cors]).await;
+}
+
+#[tokio::test]
+async fn options_and_method_not_allowed_work() {
+    use axum::http::header;
+
+    #[router]
+    #[allow(unused)]
+    enum App {
+        #[get("/todos")]
+        Todos,
+        #[post("/todos")]
+        CreateTodo,
+        #[any("/webhook")]
+        Webhook,
+        #[router]
+        Api(Api),
+        #[post("/sessions")]
+        Login,
+        #[router]
+        Sessions(Sessions),
+    }
+
+    async fn todos() {}
+    async fn create_todo() {}
+    async fn webhook() {}
+    async fn login() {}
+
+    #[resource]
+    #[allow(unused)]
+    enum Sessions {
+        Index,
+    }
+
+    impl Sessions {
+        async fn index() {}
+    }
+
+    #[router]
+    #[cors(origin = "https://example.com", headers = "content-type", max_age = 600)]
+    #[allow(unused)]
+    enum Api {
+        #[put("/api/todos/{id}")]
+        UpdateTodo(i64),
+        #[options("/api/custom")]
+        Custom,
+    }
+
+    async fn update_todo() {}
+    async fn custom() -> &'static str {
+        "custom"
+    }
+
+    let app = App::router();
+    let response = app.clone().oneshot(request("OPTIONS", "/todos")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(response.headers()[header::ALLOW], "GET, HEAD, POST, OPTIONS");
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    let response = app.clone().oneshot(request("DELETE", "/todos")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()[header::ALLOW], "GET, HEAD, POST, OPTIONS");
+
+    assert_eq!(make_request(&app, "OPTIONS", "/webhook").await, StatusCode::OK);
+
+    let response = app.clone().oneshot(request("OPTIONS", "/api/todos/1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(headers[header::ALLOW], "PUT, OPTIONS");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], "PUT, OPTIONS");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+    assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+
+    let response = app.clone().oneshot(request("GET", "/api/todos/1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    let response = app.clone().oneshot(request("OPTIONS", "/api/custom")).await.unwrap();
+    assert_eq!(body(response).await, "custom");
+    let response = app.clone().oneshot(request("GET", "/api/custom")).await.unwrap();
+    assert_eq!(response.headers()[header::ALLOW], "OPTIONS");
+
+    // a nested router sharing a path with the parent
+    assert_eq!(make_request(&app, "POST", "/sessions").await, StatusCode::OK);
+    assert_eq!(make_request(&app, "GET", "/sessions").await, StatusCode::OK);
+    let response = app.oneshot(request("DELETE", "/sessions")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()[header::ALLOW], "POST, GET, HEAD, OPTIONS");
+}
+
+#[tokio::test]
+async fn method_override_works() {
+    use axum::http::header;
+
+    #[resource]
+    #[allow(unused)]
+    enum Notes {
+        Index,
+        Create,
+        Update(i64),
+        Delete(i64),
+    }
+
+    impl Notes {
+        async fn index() -> &'static str {
+            "index"
+        }
+
+        async fn create(body: String) -> String {
+            body
+        }
+
+        async fn update(body: String) -> String {
+            format!("update {}", body)
+        }
+
+        async fn delete() -> &'static str {
+            "delete"
+        }
+    }
+
+    let form = |uri: &str, body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let app = enum_router::method_override(Notes::router());
+    let response = app
+        .clone()
+        .oneshot(form("/notes/1", "_method=PATCH&title=a"))
+        .await
+        .unwrap();
+    assert_eq!(body(response).await, "update _method=PATCH&title=a");
+    let response = app.clone().oneshot(form("/notes/1", "_method=delete")).await.unwrap();
+    assert_eq!(body(response).await, "delete");
+    let response = app.clone().oneshot(form("/notes", "title=a")).await.unwrap();
+    assert_eq!(body(response).await, "title=a");
+    let response = app.clone().oneshot(form("/notes", "_method=GET")).await.unwrap();
+    assert_eq!(body(response).await, "_method=GET");
+
+    let mut request = request("POST", "/notes/1");
+    request
+        .headers_mut()
+        .insert("x-http-method-override", "DELETE".parse().unwrap());
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(body(response).await, "delete");
+    assert_eq!(make_request(&app, "GET", "/notes").await, StatusCode::OK);
+
+    // bodies over the limit are passed on instead of buffered
+    let app = enum_router::method_override_with_limit(Notes::router(), 8);
+    let mut request = form("/notes", "_method=DELETE&title=a");
+    request
+        .headers_mut()
+        .insert(header::CONTENT_LENGTH, "22".parse().unwrap());
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(body(response).await, "_method=DELETE&title=a");
+    let response = app.oneshot(form("/notes/1", "_method=DELETE")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let fields = Notes::Delete(1).form_method_fields();
+    assert_eq!(fields.method(), "post");
+    assert_eq!(
+        fields.hidden_input(),
+        r#"<input type="hidden" name="_method" value="DELETE">"#
+    );
+    assert_eq!(fields.to_string(), r#"method="post""#);
+    assert_eq!(Notes::Create.form_method_fields().hidden_input(), "");
+    assert_eq!(Notes::Index.form_method_fields().method(), "get");
+}
+
+#[test]
+fn html_helpers_work() {
+    use enum_router::html;
+
+    #[router]
+    #[allow(unused)]
+    enum App {
+        #[get("/search")]
+        Search { page: i64, per: i64 },
+        #[post("/notes")]
+        CreateNote,
+        #[delete("/notes/{id}")]
+        DeleteNote(i64),
+    }
+
+    async fn search() {}
+    async fn create_note() {}
+    async fn delete_note() {}
+
+    let search = App::Search { page: 2, per: 10 };
+    assert_eq!(
+        search.link_to("<Next>"),
+        r#"<a href="/search?page=2&amp;per=10">&lt;Next&gt;</a>"#
+    );
+    assert_eq!(
+        search.form_open(),
+        r#"<form action="/search?page=2&amp;per=10" method="get">"#
+    );
+    assert_eq!(
+        App::CreateNote.form_open(),
+        r#"<form action="/notes" method="post">"#
+    );
+    assert_eq!(
+        App::DeleteNote(1).form_open(),
+        r#"<form action="/notes/1" method="post"><input type="hidden" name="_method" value="DELETE">"#
+    );
+    assert_eq!(
+        html::form_open(&App::CreateNote, Some("t\"k")),
+        r#"<form action="/notes" method="post"><input type="hidden" name="csrf_token" value="t&quot;k">"#
+    );
+    assert_eq!(html::escape("<a href='x'>&</a>"), "&lt;a href=&#39;x&#39;&gt;&amp;&lt;/a&gt;");
+}
+
+#[tokio::test]
+async fn matched_route_works() {
+    use axum::{extract::Request, middleware::Next, response::Response};
+    use enum_router::MatchedRoute;
+
+    #[router]
+    #[allow(unused)]
+    enum Admin {
+        #[get("/admin")]
+        Dashboard,
+        #[router]
+        Users(Users),
+        #[get("/admin/settings")]
+        Settings,
+    }
+
+    #[resource]
+    #[allow(unused)]
+    enum Users {
+        Index,
+        Show(i64),
+    }
+
+    impl Users {
+        async fn index(route: MatchedRoute) -> String {
+            format!("{}::{}", route.enum_name, route.variant)
+        }
+
+        async fn show(route: MatchedRoute) -> String {
+            format!("{}::{}", route.enum_name, route.variant)
+        }
+    }
+
+    async fn dashboard(route: MatchedRoute) -> &'static str {
+        route.pattern
+    }
+
+    async fn settings(route: MatchedRoute) -> &'static str {
+        route.handler
+    }
+
+    async fn variant_header(request: Request, next: Next) -> Response {
+        let mut response = next.run(request).await;
+        if let Some(route) = response.extensions().get::<MatchedRoute>() {
+            let value = route.variant.parse().unwrap();
+            response.headers_mut().insert("x-variant", value);
+        }
+        response
+    }
+
+    let app = Admin::router().layer(axum::middleware::from_fn(variant_header));
+    let response = app.clone().oneshot(request("GET", "/admin")).await.unwrap();
+    assert_eq!(response.headers()["x-variant"], "Dashboard");
+    assert_eq!(body(response).await, "/admin");
+    let response = app.clone().oneshot(request("GET", "/users/1")).await.unwrap();
+    assert_eq!(body(response).await, "Users::Show");
+    let response = app.clone().oneshot(request("GET", "/users")).await.unwrap();
+    assert_eq!(body(response).await, "Users::Index");
+    let response = app.oneshot(request("GET", "/admin/settings")).await.unwrap();
+    assert_eq!(response.headers()["x-variant"], "Settings");
+    assert_eq!(body(response).await, "settings");
+}
+
+#[tokio::test]
+async fn current_route_works() {
+    use axum::http::Uri;
+    use enum_router::CurrentRoute;
+
+    #[router(current_route)]
+    #[allow(unused)]
+    #[derive(Debug)]
+    enum Site {
+        #[get("/")]
+        Home,
+        #[get("/docs/{slug}")]
+        Doc(String),
+        #[get("/find")]
+        Find(#[query] Abc),
+        #[get("/search")]
+        Search { page: u32 },
+        #[router]
+        Teams(Teams),
+    }
+
+    #[resource(current_route)]
+    #[allow(unused)]
+    enum Teams {
+        Index,
+        Show(i64),
+        Edit(i64),
+    }
+
+    impl Teams {
+        async fn index(CurrentRoute(route): CurrentRoute<Site>) -> String {
+            format!("{:?}", route)
+        }
+
+        async fn show(CurrentRoute(route): CurrentRoute<Teams>) -> String {
+            format!("{:?}", route)
+        }
+
+        async fn edit(CurrentRoute(route): CurrentRoute<Site>) -> String {
+            format!("{:?}", route)
+        }
+    }
+
+    async fn home(route: CurrentRoute<Site>) -> String {
+        format!("{:?}", route.0)
+    }
+
+    async fn doc(CurrentRoute(route): CurrentRoute<Site>) -> String {
+        format!("{:?}", route)
+    }
+
+    async fn find(CurrentRoute(route): CurrentRoute<Site>) -> String {
+        format!("{:?}", route)
+    }
+
+    async fn search(CurrentRoute(route): CurrentRoute<Site>) -> String {
+        format!("{:?}", route)
+    }
+
+    let uri = |s: &str| s.parse::<Uri>().unwrap();
+    assert!(Site::Teams(Teams::Show(1)).is_current(&uri("/teams/1?tab=members")));
+    assert!(Site::Teams(Teams::Show(1)).is_current(&uri("/teams/1/")));
+    assert!(!Site::Teams(Teams::Show(1)).is_current(&uri("/teams/12")));
+    assert!(Site::Doc("a b".into()).is_current(&uri("/docs/a%20b")));
+    assert!(Site::Teams(Teams::Index).is_ancestor_of(&uri("/teams/1/edit")));
+    assert!(!Site::Teams(Teams::Index).is_ancestor_of(&uri("/teams")));
+    assert!(!Site::Teams(Teams::Index).is_ancestor_of(&uri("/teamsx/1")));
+    assert!(Site::Home.is_ancestor_of(&uri("/docs/intro")));
+    assert!(!Site::Home.is_ancestor_of(&uri("/")));
+
+    let app = Site::router();
+    for (uri, expected) in [
+        ("/", "Home"),
+        ("/docs/a%20b", r#"Doc("a b")"#),
+        ("/find?abc=7", "Find(Abc { abc: Some(7) })"),
+        ("/find", "Find(Abc { abc: None })"),
+        ("/search?page=2", "Search { page: 2 }"),
+        ("/teams", "Teams(Index)"),
+        ("/teams/3", "Show(3)"),
+        ("/teams/3/edit", "Teams(Edit(3))"),
+    ] {
+        let response = app.clone().oneshot(request("GET", uri)).await.unwrap();
+        assert_eq!(body(response).await, expected, "{}", uri);
+    }
+    assert_eq!(make_request(&app, "GET", "/search").await, StatusCode::BAD_REQUEST);
+
+    for route in [
+        Site::Doc("intro".into()),
+        Site::Search { page: 4 },
+        Site::Teams(Teams::Edit(5)),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(request("GET", &route.url().to_string()))
+            .await
+            .unwrap();
+        assert_eq!(body(response).await, format!("{:?}", route));
+    }
+}
+
+#[test]
+fn display_only_fields_work() {
+    use std::fmt;
+
+    // `Display` but not `FromStr`, fine without `current_route`
+    #[derive(Debug)]
+    struct Slug(&'static str);
+
+    impl fmt::Display for Slug {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    #[derive(QueryString)]
+    struct Search {
+        tag: Option<Slug>,
+    }
+
+    #[router]
+    #[allow(unused)]
+    enum Blog {
+        #[get("/posts/{slug}")]
+        ShowPost(Slug),
+        #[get("/search")]
+        Search(#[query] Search),
+    }
+
+    async fn show_post() {}
+    async fn search() {}
+
+    assert_eq!(Blog::ShowPost(Slug("hello")).to_string(), "/posts/hello");
+    assert_eq!(
+        Blog::Search(Search { tag: Some(Slug("rust")) }).to_string(),
+        "/search?tag=rust"
+    );
+}
+
+#[test]
+fn breadcrumbs_work() {
+    #[router]
+    #[derive(Debug, PartialEq)]
+    #[allow(unused)]
+    enum Admin {
+        #[get("/")]
+        #[title("Home")]
+        Home,
+        #[get("/projects")]
+        Projects,
+        #[get("/projects/{id}")]
+        #[parent(Admin::Projects)]
+        #[title("Project")]
+        Project(i64),
+        #[get("/projects/{id}/tasks/{task}/edit")]
+        #[parent(Admin::Project(self.0))]
+        EditTask(i64, i64),
+        #[get("/projects/{project}/members")]
+        #[parent(Admin::Project(self.project))]
+        Members { project: i64 },
+        #[router]
+        Sessions(Sessions),
+    }
+
+    #[resource]
+    #[allow(unused)]
+    #[derive(PartialEq)]
+    enum Sessions {
+        Index,
+        #[title("Edit session")]
+        Edit(i64),
+    }
+
+    impl Sessions {
+        async fn index() {}
+
+        async fn edit() {}
+    }
+
+    async fn home() {}
+    async fn projects() {}
+    async fn project() {}
+    async fn edit_task() {}
+    async fn members() {}
+
+    let crumbs = |route: Admin| route.breadcrumbs();
+    let owned = |crumbs: &[(&str, &str)]| {
+        crumbs
+            .iter()
+            .map(|(title, url)| (title.to_string(), url.to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(crumbs(Admin::Home), owned(&[("Home", "/")]));
+    assert_eq!(
+        crumbs(Admin::EditTask(1, 2)),
+        owned(&[
+            ("Home", "/"),
+            ("Projects", "/projects"),
+            ("Project", "/projects/1"),
+            ("Edit task", "/projects/1/tasks/2/edit"),
+        ])
+    );
+    assert_eq!(
+        crumbs(Admin::Sessions(Sessions::Edit(1))),
+        owned(&[
+            ("Home", "/"),
+            ("Sessions", "/sessions"),
+            ("Edit session", "/sessions/1/edit"),
+        ])
+    );
+    assert_eq!(
+        Admin::Members { project: 3 }.parent(),
+        Some(Admin::Project(3))
+    );
+    assert_eq!(Admin::Sessions(Sessions::Index).parent(), Some(Admin::Home));
+    assert_eq!(Sessions::Edit(1).parent(), Some(Sessions::Index));
+    assert_eq!(Sessions::Index.parent(), None);
+}
+
+#[tokio::test]
+async fn sitemap_works() {
+    use enum_router::sitemap::{Options, Sitemap};
+
+    #[router]
+    #[allow(unused)]
+    #[derive(Debug, PartialEq)]
+    enum Blog {
+        #[get("/")]
+        #[sitemap(changefreq = "daily", priority = 1.0)]
+        Home,
+        #[get("/posts/{id}")]
+        #[sitemap(priority = 0.85)]
+        Post(i64),
+        #[post("/posts")]
+        CreatePost,
+        #[get("/admin")]
+        #[sitemap(skip)]
+        Admin,
+        #[get("/sitemap.xml")]
+        #[sitemap(skip)]
+        Sitemap,
+        #[router]
+        Authors(Authors),
+    }
+
+    #[resource]
+    #[allow(unused)]
+    #[derive(PartialEq)]
+    enum Authors {
+        Index,
+        Show(i64),
+        Create,
+    }
+
+    impl Authors {
+        async fn index() {}
+        async fn show() {}
+        async fn create() {}
+    }
+
+    async fn home() {}
+    async fn post() {}
+    async fn create_post() {}
+    async fn admin() {}
+
+    async fn sitemap(url_for: UrlFor) -> axum::response::Response {
+        Sitemap::<Blog>::new()
+            .values(|| async { [1, 2].map(Blog::Post) })
+            .response(url_for.base())
+            .await
+    }
+
+    assert_eq!(
+        Blog::Home.sitemap_options(),
+        Some(Options { changefreq: Some("daily"), priority: Some("1.0") })
+    );
+    assert_eq!(Blog::Admin.sitemap_options(), None);
+    assert_eq!(Blog::CreatePost.sitemap_options(), None);
+    assert_eq!(Blog::Authors(Authors::Show(1)).sitemap_options(), Some(Options::default()));
+
+    let response = Blog::router()
+        .oneshot(request("GET", "/sitemap.xml"))
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-type"], "application/xml; charset=utf-8");
+    assert_eq!(
+        body(response).await,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>http://localhost/</loc>
+    <changefreq>daily</changefreq>
+    <priority>1.0</priority>
+  </url>
+  <url>
+    <loc>http://localhost/authors</loc>
+  </url>
+  <url>
+    <loc>http://localhost/posts/1</loc>
+    <priority>0.85</priority>
+  </url>
+  <url>
+    <loc>http://localhost/posts/2</loc>
+    <priority>0.85</priority>
+  </url>
+</urlset>
+"#
+    );
+
+    let app = Router::new().route(
+        "/sitemap.xml",
+        axum::routing::get(Sitemap::<Authors>::new().values(|| async { vec![Authors::Show(7)] })),
+    );
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/sitemap.xml")
+                .header("host", "example.com")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let xml = body(response).await;
+    assert!(xml.contains("<loc>http://example.com/authors</loc>"));
+    assert!(xml.contains("<loc>http://example.com/authors/7</loc>"));
+}
+
+#[cfg(feature = "coverage")]
+#[tokio::test]
+async fn coverage_works() {
+    use enum_router::coverage::{track, Coverage};
+
+    static COVERAGE: Coverage = Coverage::new(Shop::ROUTES);
+
+    let app = Shop::router().route_layer(axum::middleware::from_fn_with_state(&COVERAGE, track));
+    assert_eq!(make_request(&app, "GET", "/shop").await, StatusCode::OK);
+    assert_eq!(make_request(&app, "HEAD", "/shop").await, StatusCode::OK);
+    assert_eq!(
+        make_request(&app, "GET", "/products/1").await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        make_request(&app, "GET", "/missing").await,
+        StatusCode::NOT_FOUND
+    );
+
+    let hits = COVERAGE
+        .hits()
+        .into_iter()
+        .map(|(route, hits)| (route.variant, hits))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hits,
+        vec![("Home", 2), ("Search", 0), ("Index", 0), ("Show", 1)]
+    );
+    assert_eq!(
+        COVERAGE.report(),
+        "2/4 routes hit\n\n\
+         Method  Pattern       Route            Handler\n\
+         GET     /shop/search  Shop::Search     search\n\
+         GET     /products     Products::Index  Products::index\n"
+    );
+
+    COVERAGE.reset();
+    assert_eq!(COVERAGE.hit(), Vec::<&enum_router::RouteInfo>::new());
+    assert_eq!(COVERAGE.unhit().len(), 4);
+
+    static NESTED: Coverage = Coverage::new(Shop::ROUTES);
+
+    let app = Router::new()
+        .nest("/api", Shop::router())
+        .layer(axum::middleware::from_fn_with_state(&NESTED, track));
+    assert_eq!(
+        make_request(&app, "GET", "/api/products/1").await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        NESTED.hit().iter().map(|route| route.variant).collect::<Vec<_>>(),
+        vec!["Show"]
+    );
+}
+
+#[cfg(feature = "csrf")]
+#[tokio::test]
+async fn csrf_works() {
+    use axum::http::header;
+    use enum_router::csrf::{protect, Csrf, CsrfToken};
+
+    #[router]
+    #[allow(unused)]
+    enum App {
+        #[get("/todos/new")]
+        NewTodo,
+        #[post("/todos")]
+        CreateTodo,
+        #[post("/webhook")]
+        #[csrf(skip)]
+        Webhook,
+    }
+
+    async fn new_todo(token: CsrfToken) -> String {
+        token.to_string()
+    }
+
+    async fn create_todo(body: String) -> String {
+        body
+    }
+
+    async fn webhook() {}
+
+    // RFC 4231 test case 2
+    let csrf = Csrf::new("Jefe", App::ROUTES);
+    assert!(csrf.verify(
+        "what do ya want for nothing?.5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    ));
+    let token = csrf.token();
+    assert!(csrf.verify(token.as_str()));
+    assert!(!csrf.verify(&token.as_str().replace('.', "0.")));
+    assert!(!Csrf::new("other", App::ROUTES).verify(token.as_str()));
+    assert_ne!(token, csrf.token());
+
+    let app = App::router().layer(axum::middleware::from_fn_with_state(csrf.clone(), protect));
+    let response = app.clone().oneshot(request("GET", "/todos/new")).await.unwrap();
+    let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap().to_owned();
+    let token = body(response).await;
+    assert_eq!(
+        cookie,
+        format!("__Host-csrf_token={}; Path=/; HttpOnly; SameSite=Lax; Secure", token)
+    );
+
+    let cookie_post = |name: &str, cookie: &str, header: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/todos")
+            .header(header::COOKIE, format!("{}={}", name, cookie))
+            .header("x-csrf-token", header)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let insecure = App::router().layer(axum::middleware::from_fn_with_state(
+        csrf.clone().secure(false),
+        protect,
+    ));
+    let response = insecure.clone().oneshot(request("GET", "/todos/new")).await.unwrap();
+    assert!(response.headers()[header::SET_COOKIE]
+        .to_str()
+        .unwrap()
+        .starts_with("csrf_token="));
+    assert!(response.headers()[header::SET_COOKIE]
+        .to_str()
+        .unwrap()
+        .ends_with("; HttpOnly; SameSite=Lax"));
+    let response = insecure.oneshot(cookie_post("csrf_token", &token, &token)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app.clone().oneshot(cookie_post("csrf_token", &token, &token)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let post = |cookie: Option<&str>, header: Option<&str>, form: Option<&str>| {
+        let mut request = Request::builder().method("POST").uri("/todos");
+        if let Some(cookie) = cookie {
+            request = request.header(header::COOKIE, format!("a=b; __Host-csrf_token={}", cookie));
+        }
+        if let Some(header) = header {
+            request = request.header("x-csrf-token", header);
+        }
+        let body = match form {
+            Some(form) => {
+                request = request.header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+                Body::from(form.to_owned())
+            }
+            None => Body::empty(),
+        };
+        request.body(body).unwrap()
+    };
+
+    let response = app.clone().oneshot(post(None, None, None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app.clone().oneshot(post(Some(&token), None, None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .clone()
+        .oneshot(post(Some(&token), Some(&token), None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key(header::SET_COOKIE));
+
+    let form = format!("title=a&csrf_token={}", token);
+    let response = app
+        .clone()
+        .oneshot(post(Some(&token), None, Some(&form)))
+        .await
+        .unwrap();
+    assert_eq!(body(response).await, form);
+
+    let other = csrf.token();
+    let response = app
+        .clone()
+        .oneshot(post(Some(&token), Some(other.as_str()), None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .clone()
+        .oneshot(post(Some("forged.0"), Some("forged.0"), None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    assert_eq!(make_request(&app, "POST", "/webhook").await, StatusCode::OK);
+    assert_eq!(make_request(&app, "POST", "/missing").await, StatusCode::FORBIDDEN);
+
+    // under a prefix the skipped route isn't recognized, and every route needs a token
+    let nested = Router::new()
+        .nest("/api", App::router())
+        .layer(axum::middleware::from_fn_with_state(csrf.clone(), protect));
+    assert_eq!(make_request(&nested, "POST", "/api/todos").await, StatusCode::FORBIDDEN);
+    assert_eq!(make_request(&nested, "POST", "/api/webhook").await, StatusCode::FORBIDDEN);
+    assert_eq!(
+        enum_router::html::form_open(&App::CreateTodo, Some(&token)),
+        format!(
+            r#"<form action="/todos" method="post"><input type="hidden" name="csrf_token" value="{}">"#,
+            token
+        )
+    );
+}
+
+async fn body(response: axum::response::Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
 fn request(method: &str, uri: &str) -> Request<Body> {
     Request::builder()
         .method(method)